@@ -7,8 +7,8 @@ use pyo3::types::PyDict;
 use scriptmark::discovery::discover_submissions;
 use scriptmark::grading::apply_grading;
 use scriptmark::models::{StudentReport, TestSpec};
+use scriptmark::runner::executor::ExecutorRegistry;
 use scriptmark::runner::orchestrator::run_all;
-use scriptmark::runner::python::PythonExecutor;
 use scriptmark::spec_loader::load_specs_from_dir;
 
 /// A test specification loaded from a TOML file.
@@ -208,13 +208,13 @@ fn run_grading(
 	let specs = load_specs_from_dir(Path::new(tests))
 		.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
 
-	let executor = PythonExecutor::with_python_cmd(python);
+	let executors = ExecutorRegistry::builtin(python);
 
 	// Bridge sync PyO3 → async tokio
 	let rt = tokio::runtime::Runtime::new()
 		.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
 
-	let results = rt.block_on(run_all(&subs, &specs, &executor, timeout, None));
+	let results = rt.block_on(run_all(&subs, &specs, &executors, timeout, None));
 	Ok(results)
 }
 
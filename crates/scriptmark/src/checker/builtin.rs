@@ -1,11 +1,14 @@
 use super::{CheckInput, CheckOutput, Checker};
 
 /// Exact equality checker (default).
+///
+/// Numbers compare by value, so `1` equals `1.0`; use `expect_type` to tell
+/// an int from a float.
 pub struct ExactChecker;
 
 impl Checker for ExactChecker {
 	fn check(&self, input: &CheckInput) -> CheckOutput {
-		if input.result == input.expected {
+		if json_eq(&input.result, &input.expected) {
 			CheckOutput {
 				pass: true,
 				message: String::new(),
@@ -19,6 +22,26 @@ impl Checker for ExactChecker {
 	}
 }
 
+/// Structural equality where numbers compare by value.
+fn json_eq(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+	use serde_json::Value;
+	match (a, b) {
+		(Value::Number(x), Value::Number(y)) => match (x.as_i64(), y.as_i64()) {
+			(Some(x), Some(y)) => x == y,
+			_ => x == y || x.as_f64() == y.as_f64(),
+		},
+		(Value::Array(x), Value::Array(y)) => {
+			x.len() == y.len() && x.iter().zip(y).all(|(x, y)| json_eq(x, y))
+		}
+		(Value::Object(x), Value::Object(y)) => {
+			x.len() == y.len()
+				&& x.iter()
+					.all(|(k, v)| y.get(k).is_some_and(|w| json_eq(v, w)))
+		}
+		_ => a == b,
+	}
+}
+
 /// Approximate floating-point checker.
 pub struct ApproxChecker {
 	pub tolerance: f64,
@@ -158,8 +181,45 @@ impl Checker for ContainsChecker {
 }
 
 /// Check that the result matches a regex pattern.
+///
+/// Searches anywhere in the output unless `full_match` is set.
 pub struct RegexChecker {
 	pub pattern: regex::Regex,
+	pub full_match: bool,
+	/// The pattern as written in the spec, for failure messages.
+	pub source: String,
+}
+
+impl RegexChecker {
+	/// Compile `pattern` with single-letter `flags` (`i`, `m`, `s`, `x`).
+	pub fn new(pattern: &str, flags: &str, full_match: bool) -> Result<Self, String> {
+		let source = if full_match {
+			// Under `x`, a trailing `# comment` would swallow the closing
+			// group; the newline ends it and is otherwise ignored
+			let end = if flags.contains('x') { "\n" } else { "" };
+			format!(r"\A(?:{pattern}{end})\z")
+		} else {
+			pattern.to_string()
+		};
+		let mut builder = regex::RegexBuilder::new(&source);
+		for flag in flags.chars() {
+			match flag {
+				'i' => builder.case_insensitive(true),
+				'm' => builder.multi_line(true),
+				's' => builder.dot_matches_new_line(true),
+				'x' => builder.ignore_whitespace(true),
+				other => return Err(format!("unknown regex flag '{other}' in '{flags}'")),
+			};
+		}
+		let compiled = builder
+			.build()
+			.map_err(|e| format!("invalid regex '{pattern}': {e}"))?;
+		Ok(Self {
+			pattern: compiled,
+			full_match,
+			source: pattern.to_string(),
+		})
+	}
 }
 
 impl Checker for RegexChecker {
@@ -173,9 +233,13 @@ impl Checker for RegexChecker {
 				message: String::new(),
 			}
 		} else {
+			let mode = if self.full_match { "fully " } else { "" };
 			CheckOutput {
 				pass: false,
-				message: format!("output does not match pattern '{}'", self.pattern),
+				message: format!(
+					"output {actual:?} does not {mode}match pattern '{}'",
+					self.source
+				),
 			}
 		}
 	}
@@ -265,6 +329,23 @@ mod tests {
 		assert!(result.message.contains("expected 5"));
 	}
 
+	#[test]
+	fn test_exact_numbers_compare_by_value() {
+		let check = |result, expected| {
+			ExactChecker
+				.check(&CheckInput {
+					result,
+					expected,
+					context: json!({}),
+				})
+				.pass
+		};
+		assert!(check(json!(1.0), json!(1)));
+		assert!(check(json!({"a": [2, 3.0]}), json!({"a": [2.0, 3]})));
+		assert!(!check(json!(1.5), json!(1)));
+		assert!(!check(json!([1, 2]), json!([1, 2, 3])));
+	}
+
 	#[test]
 	fn test_approx_pass() {
 		let checker = ApproxChecker { tolerance: 0.001 };
@@ -342,6 +423,36 @@ mod tests {
 		assert!(!result.pass);
 	}
 
+	#[test]
+	fn test_regex_search_full_match_and_flags() {
+		let input = |s: &str| CheckInput {
+			result: json!(s),
+			expected: json!(null),
+			context: json!({}),
+		};
+
+		let search = RegexChecker::new(r"\d+", "", false).unwrap();
+		assert!(search.check(&input("total: 42")).pass);
+
+		let full = RegexChecker::new(r"\d+", "", true).unwrap();
+		assert!(!full.check(&input("total: 42")).pass);
+		assert!(full.check(&input("42")).pass);
+		assert_eq!(
+			full.check(&input("x")).message,
+			r#"output "x" does not fully match pattern '\d+'"#
+		);
+
+		let commented = RegexChecker::new(r"\d+  # digits only", "x", true).unwrap();
+		assert!(commented.check(&input("42")).pass);
+		assert!(!commented.check(&input("42 apples")).pass);
+
+		let lines = RegexChecker::new(r"^done$", "im", false).unwrap();
+		assert!(lines.check(&input("working\nDONE\n")).pass);
+
+		assert!(RegexChecker::new("(unclosed", "", false).is_err());
+		assert!(RegexChecker::new("a", "q", false).is_err());
+	}
+
 	#[test]
 	fn test_set_eq() {
 		let checker = SetEqChecker;
@@ -0,0 +1,261 @@
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use super::{CheckInput, CheckOutput, Checker};
+use crate::runner::process::SANDBOX_PATH;
+use crate::runner::sandbox::SandboxConfig;
+
+/// Checker that runs an arbitrary verifier executable (C++, Bash, ...).
+///
+/// Same protocol as [`PythonChecker`](super::python_checker::PythonChecker):
+/// - stdin:  JSON `{"result": ..., "expected": ..., "context": {...}}`
+/// - stdout: JSON `{"pass": true/false, "message": "..."}`
+///
+/// The command is split on whitespace, so `"verifiers/check.sh --strict"`
+/// passes `--strict` to the script. It runs sandboxed with a cleared
+/// environment.
+pub struct ExecChecker {
+	pub program: String,
+	pub args: Vec<String>,
+	pub timeout_secs: u64,
+	pub sandbox: SandboxConfig,
+}
+
+impl ExecChecker {
+	pub fn new(command: &str) -> Self {
+		let mut parts = command.split_whitespace().map(String::from);
+		Self {
+			program: parts.next().unwrap_or_default(),
+			args: parts.collect(),
+			timeout_secs: 10,
+			sandbox: SandboxConfig::default(),
+		}
+	}
+
+	pub fn with_timeout(mut self, secs: u64) -> Self {
+		self.timeout_secs = secs;
+		self
+	}
+
+	pub fn with_sandbox(mut self, sandbox: SandboxConfig) -> Self {
+		self.sandbox = sandbox;
+		self
+	}
+}
+
+impl Checker for ExecChecker {
+	fn check(&self, input: &CheckInput) -> CheckOutput {
+		let mut cmd = Command::new(&self.program);
+		cmd.args(&self.args)
+			.env_clear()
+			.env("PATH", SANDBOX_PATH)
+			.env("HOME", "/tmp");
+		#[cfg(unix)]
+		crate::runner::sandbox::apply_sandbox_std(&mut cmd, &self.sandbox);
+
+		run_verifier(
+			cmd,
+			&format!("Exec checker '{}'", self.program),
+			input,
+			self.timeout_secs,
+		)
+	}
+}
+
+/// Run a verifier process with the JSON checker protocol.
+///
+/// `label` names the verifier in failure messages (e.g. `Python checker 'x.py'`).
+pub(crate) fn run_verifier(
+	mut cmd: Command,
+	label: &str,
+	input: &CheckInput,
+	timeout_secs: u64,
+) -> CheckOutput {
+	let input_json = match serde_json::to_string(input) {
+		Ok(j) => j,
+		Err(e) => {
+			return CheckOutput {
+				pass: false,
+				message: format!("Failed to serialize checker input: {e}"),
+			};
+		}
+	};
+
+	// Own process group, so a timeout also kills anything the verifier spawned
+	#[cfg(unix)]
+	std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+
+	let mut child = match cmd
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+	{
+		Ok(c) => c,
+		Err(e) => {
+			return CheckOutput {
+				pass: false,
+				message: format!("Failed to spawn {label}: {e}"),
+			};
+		}
+	};
+
+	// Feed stdin and drain the pipes on threads so a chatty verifier can't
+	// deadlock against a full pipe buffer
+	let stdin = child.stdin.take();
+	let writer = std::thread::spawn(move || {
+		if let Some(mut stdin) = stdin {
+			let _ = stdin.write_all(input_json.as_bytes());
+		}
+	});
+	let stdout = drain(child.stdout.take());
+	let stderr = drain(child.stderr.take());
+
+	let result = child.wait_timeout(Duration::from_secs(timeout_secs));
+	if !matches!(result, Ok(Some(_))) {
+		#[cfg(unix)]
+		// SAFETY: plain syscall; the negative pid targets the child's own group.
+		unsafe {
+			libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+		}
+		let _ = child.kill();
+		let _ = child.wait();
+	}
+	let _ = writer.join();
+	let stdout = stdout.join().unwrap_or_default();
+	let stderr = stderr.join().unwrap_or_default();
+
+	match result {
+		Ok(Some(status)) => {
+			if !status.success() && stdout.trim().is_empty() {
+				return CheckOutput {
+					pass: false,
+					message: format!("{label} exited with {status}: {}", stderr.trim()),
+				};
+			}
+
+			// Parse JSON output
+			match serde_json::from_str::<CheckOutput>(stdout.trim()) {
+				Ok(output) => output,
+				Err(e) => CheckOutput {
+					pass: false,
+					message: format!(
+						"Failed to parse checker output: {e}\nRaw output: {}",
+						stdout.trim()
+					),
+				},
+			}
+		}
+		Ok(None) => CheckOutput {
+			pass: false,
+			message: format!("{label} timed out after {timeout_secs}s"),
+		},
+		Err(e) => CheckOutput {
+			pass: false,
+			message: format!("Error waiting for {label}: {e}"),
+		},
+	}
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<String> {
+	std::thread::spawn(move || {
+		let mut buf = String::new();
+		if let Some(mut p) = pipe {
+			let _ = p.read_to_string(&mut buf);
+		}
+		buf
+	})
+}
+
+// wait_timeout is not in std — implement by polling try_wait
+trait WaitTimeout {
+	fn wait_timeout(
+		&mut self,
+		timeout: Duration,
+	) -> std::io::Result<Option<std::process::ExitStatus>>;
+}
+
+impl WaitTimeout for std::process::Child {
+	fn wait_timeout(
+		&mut self,
+		timeout: Duration,
+	) -> std::io::Result<Option<std::process::ExitStatus>> {
+		use std::thread;
+
+		let start = std::time::Instant::now();
+		let poll_interval = Duration::from_millis(10);
+
+		loop {
+			match self.try_wait()? {
+				Some(status) => {
+					return Ok(Some(status));
+				}
+				None => {
+					if start.elapsed() >= timeout {
+						return Ok(None);
+					}
+					thread::sleep(poll_interval.min(timeout - start.elapsed()));
+				}
+			}
+		}
+	}
+}
+
+// The verifiers below are executable shell scripts
+#[cfg(all(test, unix))]
+mod tests {
+	use std::os::unix::fs::PermissionsExt;
+
+	use super::*;
+	use serde_json::json;
+
+	fn write_script(dir: &std::path::Path, name: &str, code: &str) -> String {
+		let path = dir.join(name);
+		std::fs::write(&path, code).unwrap();
+		std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+		path.to_string_lossy().into_owned()
+	}
+
+	fn input(result: serde_json::Value) -> CheckInput {
+		CheckInput {
+			result,
+			expected: json!(null),
+			context: json!({}),
+		}
+	}
+
+	#[test]
+	fn test_exec_checker_bash_verifier() {
+		let dir = tempfile::tempdir().unwrap();
+		let script = write_script(
+			dir.path(),
+			"check.sh",
+			"#!/bin/sh\nif grep -q '\"result\":42' ; then echo '{\"pass\": true}'; else echo \"{\\\"pass\\\": false, \\\"message\\\": \\\"$1\\\"}\"; fi\n",
+		);
+
+		let checker = ExecChecker::new(&format!("{script} wrong-answer"));
+		assert!(checker.check(&input(json!(42))).pass);
+		let output = checker.check(&input(json!(7)));
+		assert!(!output.pass);
+		assert_eq!(output.message, "wrong-answer");
+	}
+
+	#[test]
+	fn test_exec_checker_timeout_and_bad_output() {
+		let dir = tempfile::tempdir().unwrap();
+		let slow = write_script(dir.path(), "slow.sh", "#!/bin/sh\nsleep 5\n");
+		let output = ExecChecker::new(&slow)
+			.with_timeout(1)
+			.check(&input(json!(1)));
+		assert!(!output.pass);
+		assert!(output.message.contains("timed out"), "{}", output.message);
+
+		let noisy = write_script(dir.path(), "noisy.sh", "#!/bin/sh\necho not json\n");
+		let output = ExecChecker::new(&noisy).check(&input(json!(1)));
+		assert!(output.message.starts_with("Failed to parse checker output"));
+
+		let output = ExecChecker::new("/nonexistent/verifier").check(&input(json!(1)));
+		assert!(output.message.starts_with("Failed to spawn Exec checker"));
+	}
+}
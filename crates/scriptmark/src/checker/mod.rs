@@ -1,9 +1,18 @@
 pub mod builtin;
+pub mod exec_checker;
 pub mod python_checker;
 pub mod rhai_checker;
+pub mod wasm_checker;
 
 use serde::{Deserialize, Serialize};
 
+use crate::models::{CheckMethod, CheckSpec};
+use builtin::{ExactChecker, RegexChecker, resolve_builtin};
+use exec_checker::ExecChecker;
+use python_checker::PythonChecker;
+use rhai_checker::RhaiChecker;
+use wasm_checker::WasmChecker;
+
 /// Input to a checker.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CheckInput {
@@ -29,3 +38,75 @@ pub struct CheckOutput {
 pub trait Checker: Send + Sync {
 	fn check(&self, input: &CheckInput) -> CheckOutput;
 }
+
+/// Resolve which checker to use for a test case. Defaults to exact match.
+///
+/// `python_cmd` is the interpreter used for Python verifier scripts.
+pub fn resolve_checker(check: Option<&CheckMethod>, python_cmd: &str) -> Box<dyn Checker> {
+	if let Some(check) = check {
+		match check {
+			CheckMethod::Builtin(name) => {
+				if let Some(c) = resolve_builtin(name, None) {
+					return c;
+				}
+			}
+			CheckMethod::Detailed(spec) => {
+				if let Some(name) = &spec.builtin
+					&& let Some(c) = resolve_builtin(name, spec.tolerance)
+				{
+					return c;
+				}
+				if let Some(pattern) = &spec.regex {
+					return match regex_checker(spec, pattern) {
+						Ok(c) => Box::new(c),
+						Err(message) => Box::new(InvalidChecker(message)),
+					};
+				}
+				if let Some(expr) = &spec.rhai {
+					return Box::new(RhaiChecker::new(expr));
+				}
+				if let Some(script) = &spec.python {
+					return Box::new(PythonChecker::new(script).with_python_cmd(python_cmd));
+				}
+				if let Some(command) = &spec.exec {
+					return Box::new(ExecChecker::new(command));
+				}
+				if let Some(module) = &spec.wasm {
+					return Box::new(WasmChecker::new(module));
+				}
+			}
+		}
+	}
+	Box::new(ExactChecker)
+}
+
+/// Check a case's checker configuration up front, so a bad spec is reported
+/// when it is loaded rather than as a failure on every submission.
+pub fn validate_check(check: &CheckMethod) -> Result<(), String> {
+	if let CheckMethod::Detailed(spec) = check
+		&& let Some(pattern) = &spec.regex
+	{
+		regex_checker(spec, pattern)?;
+	}
+	Ok(())
+}
+
+fn regex_checker(spec: &CheckSpec, pattern: &str) -> Result<RegexChecker, String> {
+	RegexChecker::new(
+		pattern,
+		spec.flags.as_deref().unwrap_or(""),
+		spec.full_match,
+	)
+}
+
+/// Stands in for a checker whose configuration is broken; fails every case.
+struct InvalidChecker(String);
+
+impl Checker for InvalidChecker {
+	fn check(&self, _input: &CheckInput) -> CheckOutput {
+		CheckOutput {
+			pass: false,
+			message: format!("invalid checker: {}", self.0),
+		}
+	}
+}
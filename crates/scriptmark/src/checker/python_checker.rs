@@ -1,8 +1,7 @@
-use std::io::Write;
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
-use std::time::Duration;
+use std::process::Command;
 
+use super::exec_checker::run_verifier;
 use super::{CheckInput, CheckOutput, Checker};
 
 /// Checker that runs a Python verification script.
@@ -38,137 +37,14 @@ impl PythonChecker {
 
 impl Checker for PythonChecker {
 	fn check(&self, input: &CheckInput) -> CheckOutput {
-		let input_json = match serde_json::to_string(input) {
-			Ok(j) => j,
-			Err(e) => {
-				return CheckOutput {
-					pass: false,
-					message: format!("Failed to serialize checker input: {e}"),
-				};
-			}
-		};
-
-		let mut child = match Command::new(&self.python_cmd)
-			.arg(&self.script_path)
-			.stdin(Stdio::piped())
-			.stdout(Stdio::piped())
-			.stderr(Stdio::piped())
-			.spawn()
-		{
-			Ok(c) => c,
-			Err(e) => {
-				return CheckOutput {
-					pass: false,
-					message: format!(
-						"Failed to spawn Python checker '{}': {e}",
-						self.script_path.display()
-					),
-				};
-			}
-		};
-
-		// Write input to stdin
-		if let Some(mut stdin) = child.stdin.take() {
-			let _ = stdin.write_all(input_json.as_bytes());
-		}
-
-		// Wait with timeout
-		let result = child.wait_timeout(Duration::from_secs(self.timeout_secs));
-
-		match result {
-			Ok(Some(status)) => {
-				let stdout = {
-					use std::io::Read;
-					let mut buf = String::new();
-					if let Some(mut out) = child.stdout.take() {
-						let _ = out.read_to_string(&mut buf);
-					}
-					buf
-				};
-
-				if !status.success() && stdout.trim().is_empty() {
-					let stderr = {
-						use std::io::Read;
-						let mut buf = String::new();
-						if let Some(mut err) = child.stderr.take() {
-							let _ = err.read_to_string(&mut buf);
-						}
-						buf
-					};
-					return CheckOutput {
-						pass: false,
-						message: format!(
-							"Python checker '{}' exited with {}: {}",
-							self.script_path.display(),
-							status,
-							stderr.trim()
-						),
-					};
-				}
-
-				// Parse JSON output
-				match serde_json::from_str::<CheckOutput>(stdout.trim()) {
-					Ok(output) => output,
-					Err(e) => CheckOutput {
-						pass: false,
-						message: format!(
-							"Failed to parse checker output: {e}\nRaw output: {}",
-							stdout.trim()
-						),
-					},
-				}
-			}
-			Ok(None) => {
-				// Timeout — kill the process
-				let _ = child.kill();
-				CheckOutput {
-					pass: false,
-					message: format!(
-						"Python checker '{}' timed out after {}s",
-						self.script_path.display(),
-						self.timeout_secs
-					),
-				}
-			}
-			Err(e) => CheckOutput {
-				pass: false,
-				message: format!("Error waiting for Python checker: {e}"),
-			},
-		}
-	}
-}
-
-// wait_timeout is not in std — implement using a thread
-trait WaitTimeout {
-	fn wait_timeout(
-		&mut self,
-		timeout: Duration,
-	) -> std::io::Result<Option<std::process::ExitStatus>>;
-}
-
-impl WaitTimeout for std::process::Child {
-	fn wait_timeout(
-		&mut self,
-		timeout: Duration,
-	) -> std::io::Result<Option<std::process::ExitStatus>> {
-		use std::thread;
-
-		let start = std::time::Instant::now();
-		let poll_interval = Duration::from_millis(10);
-
-		loop {
-			match self.try_wait()? {
-				Some(status) => {
-					return Ok(Some(status));
-				}
-				None => {
-					if start.elapsed() >= timeout {
-						return Ok(None);
-					}
-					thread::sleep(poll_interval.min(timeout - start.elapsed()));
-				}
-			}
-		}
+		let mut cmd = Command::new(&self.python_cmd);
+		cmd.arg(&self.script_path);
+		run_verifier(
+			cmd,
+			&format!("Python checker '{}'", self.script_path.display()),
+			input,
+			self.timeout_secs,
+		)
 	}
 }
 
@@ -47,6 +47,43 @@ pub fn json_to_dynamic(value: &serde_json::Value) -> Dynamic {
 	}
 }
 
+/// Convert a Rhai Dynamic value back to a serde_json::Value.
+///
+/// Arrays and maps convert recursively; values with no JSON counterpart
+/// (function pointers, timestamps, custom types) are an error naming the type.
+pub fn dynamic_to_json(value: &Dynamic) -> Result<serde_json::Value, String> {
+	if value.is_unit() {
+		Ok(serde_json::Value::Null)
+	} else if let Ok(b) = value.as_bool() {
+		Ok(serde_json::Value::from(b))
+	} else if let Ok(i) = value.as_int() {
+		Ok(serde_json::Value::from(i))
+	} else if let Ok(f) = value.as_float() {
+		serde_json::Number::from_f64(f)
+			.map(serde_json::Value::Number)
+			.ok_or_else(|| format!("{f} has no JSON representation"))
+	} else if let Ok(c) = value.as_char() {
+		Ok(serde_json::Value::from(c.to_string()))
+	} else if value.is_string() {
+		Ok(serde_json::Value::from(value.clone().into_string()?))
+	} else if value.is_array() {
+		let items = value.read_lock::<rhai::Array>().ok_or("array is locked")?;
+		items
+			.iter()
+			.map(dynamic_to_json)
+			.collect::<Result<Vec<_>, _>>()
+			.map(serde_json::Value::Array)
+	} else if value.is_map() {
+		let map = value.read_lock::<rhai::Map>().ok_or("map is locked")?;
+		map.iter()
+			.map(|(k, v)| Ok((k.to_string(), dynamic_to_json(v)?)))
+			.collect::<Result<serde_json::Map<_, _>, String>>()
+			.map(serde_json::Value::Object)
+	} else {
+		Err(format!("cannot convert a {} to JSON", value.type_name()))
+	}
+}
+
 impl Checker for RhaiChecker {
 	fn check(&self, input: &CheckInput) -> CheckOutput {
 		let engine = Engine::new();
@@ -175,4 +212,16 @@ mod tests {
 		assert!(!output.pass);
 		assert!(output.message.contains("must return bool"));
 	}
+
+	#[test]
+	fn test_dynamic_to_json_round_trips_collections() {
+		let value = json!({"nums": [3, 1.5, null], "name": "x", "nested": {"ok": true}});
+		assert_eq!(dynamic_to_json(&json_to_dynamic(&value)).unwrap(), value);
+
+		let engine = Engine::new();
+		let sorted: Dynamic = engine.eval("let a = [3, 1, 2]; a.sort(); a").unwrap();
+		assert_eq!(dynamic_to_json(&sorted).unwrap(), json!([1, 2, 3]));
+		let pointer: Dynamic = engine.eval("Fn(\"len\")").unwrap();
+		assert!(dynamic_to_json(&pointer).unwrap_err().contains("Fn"));
+	}
 }
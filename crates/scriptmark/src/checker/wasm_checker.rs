@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::time::SystemTime;
+
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use super::{CheckInput, CheckOutput, Checker};
+
+/// Checker that runs a WebAssembly verifier in an embedded interpreter.
+///
+/// The module gets no imports (no filesystem, network or clock), so it
+/// behaves the same on every machine. ABI:
+/// - export `memory`
+/// - export `alloc(len: i32) -> i32`: reserve `len` bytes for the input
+/// - export `check(ptr: i32, len: i32) -> i64`: read the `CheckInput` JSON at
+///   `ptr`, return `(out_ptr << 32) | out_len` pointing at `CheckOutput` JSON
+///
+/// Execution is bounded by fuel (roughly one unit per instruction) and a cap
+/// on linear memory. The module is compiled once per process (see
+/// [`load_module`]); every check gets a fresh instance.
+pub struct WasmChecker {
+	pub module_path: PathBuf,
+	pub fuel: u64,
+	pub max_memory_bytes: usize,
+	compiled: OnceLock<Result<Arc<CompiledModule>, String>>,
+}
+
+type CompiledModule = (Engine, Module);
+
+/// Compiled modules by canonical path, with the mtime they were read at.
+type ModuleCache = HashMap<PathBuf, (Option<SystemTime>, Arc<CompiledModule>)>;
+
+static MODULES: LazyLock<Mutex<ModuleCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Paths compiled so far, so tests can count compilations.
+#[cfg(test)]
+static COMPILED_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// The engine and compiled module for `path`, shared by every checker in the
+/// process. A module whose file changed since it was compiled is recompiled.
+fn load_module(path: &Path) -> Result<Arc<CompiledModule>, String> {
+	let path = path
+		.canonicalize()
+		.map_err(|e| format!("cannot read module: {e}"))?;
+	let mtime = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+	let mut modules = MODULES.lock().unwrap_or_else(|e| e.into_inner());
+	if let Some((cached_mtime, compiled)) = modules.get(&path)
+		&& *cached_mtime == mtime
+	{
+		return Ok(Arc::clone(compiled));
+	}
+
+	let wasm = std::fs::read(&path).map_err(|e| format!("cannot read module: {e}"))?;
+	let mut config = Config::default();
+	config.consume_fuel(true);
+	let engine = Engine::new(&config);
+	let module = Module::new(&engine, &wasm).map_err(|e| format!("invalid module: {e}"))?;
+	#[cfg(test)]
+	COMPILED_PATHS
+		.lock()
+		.unwrap_or_else(|e| e.into_inner())
+		.push(path.clone());
+
+	let compiled = Arc::new((engine, module));
+	modules.insert(path, (mtime, Arc::clone(&compiled)));
+	Ok(compiled)
+}
+
+struct HostState {
+	limits: StoreLimits,
+}
+
+impl WasmChecker {
+	pub fn new(module_path: impl Into<PathBuf>) -> Self {
+		Self {
+			module_path: module_path.into(),
+			fuel: 1_000_000_000,
+			max_memory_bytes: 64 * 1024 * 1024,
+			compiled: OnceLock::new(),
+		}
+	}
+
+	pub fn with_fuel(mut self, fuel: u64) -> Self {
+		self.fuel = fuel;
+		self
+	}
+
+	pub fn with_max_memory(mut self, bytes: usize) -> Self {
+		self.max_memory_bytes = bytes;
+		self
+	}
+
+	/// The engine and compiled module, looked up once per checker.
+	fn compiled(&self) -> Result<&CompiledModule, String> {
+		self.compiled
+			.get_or_init(|| load_module(&self.module_path))
+			.as_ref()
+			.map(|compiled| &**compiled)
+			.map_err(Clone::clone)
+	}
+
+	fn run(&self, input_json: &[u8]) -> Result<Vec<u8>, String> {
+		let (engine, module) = self.compiled()?;
+
+		let mut store = Store::new(
+			engine,
+			HostState {
+				limits: StoreLimitsBuilder::new()
+					.memory_size(self.max_memory_bytes)
+					.memories(1)
+					.instances(1)
+					.build(),
+			},
+		);
+		store.limiter(|state| &mut state.limits);
+		store
+			.set_fuel(self.fuel)
+			.map_err(|e| format!("cannot set fuel: {e}"))?;
+
+		let trap = |e: wasmi::Error| {
+			if e.as_trap_code() == Some(wasmi::core::TrapCode::OutOfFuel) {
+				format!("ran out of fuel ({} units)", self.fuel)
+			} else {
+				format!("trapped: {e}")
+			}
+		};
+
+		let instance = Linker::<HostState>::new(engine)
+			.instantiate(&mut store, module)
+			.and_then(|pre| pre.start(&mut store))
+			.map_err(trap)?;
+		let memory = instance
+			.get_memory(&store, "memory")
+			.ok_or("missing export `memory`")?;
+		let alloc = instance
+			.get_typed_func::<i32, i32>(&store, "alloc")
+			.map_err(|e| format!("bad export `alloc`: {e}"))?;
+		let check = instance
+			.get_typed_func::<(i32, i32), i64>(&store, "check")
+			.map_err(|e| format!("bad export `check`: {e}"))?;
+
+		let len = i32::try_from(input_json.len()).map_err(|_| "input too large")?;
+		let ptr = alloc.call(&mut store, len).map_err(trap)?;
+		memory
+			.write(&mut store, ptr as u32 as usize, input_json)
+			.map_err(|e| format!("`alloc` returned an invalid pointer: {e}"))?;
+
+		let packed = check.call(&mut store, (ptr, len)).map_err(trap)? as u64;
+		let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+		let mut out = vec![0; out_len];
+		memory
+			.read(&store, out_ptr, &mut out)
+			.map_err(|e| format!("`check` returned an invalid output range: {e}"))?;
+		Ok(out)
+	}
+}
+
+impl Checker for WasmChecker {
+	fn check(&self, input: &CheckInput) -> CheckOutput {
+		let input_json = match serde_json::to_vec(input) {
+			Ok(j) => j,
+			Err(e) => {
+				return CheckOutput {
+					pass: false,
+					message: format!("Failed to serialize checker input: {e}"),
+				};
+			}
+		};
+
+		let output = match self.run(&input_json) {
+			Ok(o) => o,
+			Err(e) => {
+				return CheckOutput {
+					pass: false,
+					message: format!("WASM checker '{}' {e}", self.module_path.display()),
+				};
+			}
+		};
+
+		match serde_json::from_slice::<CheckOutput>(&output) {
+			Ok(output) => output,
+			Err(e) => CheckOutput {
+				pass: false,
+				message: format!(
+					"Failed to parse checker output: {e}\nRaw output: {}",
+					String::from_utf8_lossy(&output)
+				),
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	/// Passes when the input JSON contains the digits "42".
+	const FIND_42: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+  (data (i32.const 0) "{\"pass\":true}")
+  (data (i32.const 64) "{\"pass\":false,\"message\":\"no 42\"}")
+  (func (export "alloc") (param $n i32) (result i32)
+    (local $p i32)
+    (local.set $p (global.get $heap))
+    (global.set $heap (i32.add (global.get $heap) (local.get $n)))
+    (local.get $p))
+  (func (export "check") (param $ptr i32) (param $len i32) (result i64)
+    (local $i i32)
+    (block $done
+      (loop $scan
+        (br_if $done (i32.ge_u (i32.add (local.get $i) (i32.const 1)) (local.get $len)))
+        (if (i32.and
+              (i32.eq (i32.load8_u (i32.add (local.get $ptr) (local.get $i))) (i32.const 52))
+              (i32.eq (i32.load8_u offset=1 (i32.add (local.get $ptr) (local.get $i))) (i32.const 50)))
+          (then (return (i64.const 13))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $scan)))
+    (i64.or (i64.shl (i64.const 64) (i64.const 32)) (i64.const 32))))
+"#;
+
+	const SPIN: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) (i32.const 0))
+  (func (export "check") (param i32 i32) (result i64)
+    (loop $forever (br $forever))
+    (unreachable)))
+"#;
+
+	fn write_module(dir: &std::path::Path, name: &str, wat_src: &str) -> PathBuf {
+		let path = dir.join(name);
+		std::fs::write(&path, wat::parse_str(wat_src).unwrap()).unwrap();
+		path
+	}
+
+	fn input(result: serde_json::Value) -> CheckInput {
+		CheckInput {
+			result,
+			expected: json!(null),
+			context: json!({}),
+		}
+	}
+
+	#[test]
+	fn test_wasm_checker_reads_input_and_reports() {
+		let dir = tempfile::tempdir().unwrap();
+		let checker = WasmChecker::new(write_module(dir.path(), "find42.wasm", FIND_42));
+
+		assert!(checker.check(&input(json!(42))).pass);
+		let output = checker.check(&input(json!(7)));
+		assert!(!output.pass);
+		assert_eq!(output.message, "no 42");
+
+		// Compiled once: later checks no longer read the file
+		std::fs::remove_file(&checker.module_path).unwrap();
+		assert!(checker.check(&input(json!(42))).pass);
+	}
+
+	#[test]
+	fn test_resolved_checkers_share_compiled_module() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = write_module(dir.path(), "shared.wasm", FIND_42);
+		let check = crate::models::CheckMethod::Detailed(
+			toml::from_str(&format!("wasm = \"{}\"", path.display())).unwrap(),
+		);
+
+		for value in [42, 7] {
+			let checker = crate::checker::resolve_checker(Some(&check), "python3");
+			assert_eq!(checker.check(&input(json!(value))).pass, value == 42);
+		}
+		let canonical = path.canonicalize().unwrap();
+		let compilations = COMPILED_PATHS
+			.lock()
+			.unwrap()
+			.iter()
+			.filter(|p| **p == canonical)
+			.count();
+		assert_eq!(compilations, 1);
+	}
+
+	#[test]
+	fn test_wasm_checker_fuel_limit() {
+		let dir = tempfile::tempdir().unwrap();
+		let checker =
+			WasmChecker::new(write_module(dir.path(), "spin.wasm", SPIN)).with_fuel(10_000);
+		let output = checker.check(&input(json!(1)));
+		assert!(!output.pass);
+		assert!(
+			output.message.contains("ran out of fuel"),
+			"{}",
+			output.message
+		);
+
+		let output = WasmChecker::new(dir.path().join("missing.wasm")).check(&input(json!(1)));
+		assert!(output.message.contains("cannot read module"));
+	}
+}
@@ -98,6 +98,7 @@ mod tests {
 					expected: None,
 					failure: None,
 					elapsed_ms: None,
+					matched_name: None,
 				}],
 			}],
 			final_grade: Some(95.0),
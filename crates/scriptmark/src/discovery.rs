@@ -3,16 +3,17 @@ use std::io::Read as _;
 use std::path::{Path, PathBuf};
 
 use crate::models::{StudentFile, SubmissionSet};
+use crate::notebook;
 
 /// Map file extensions to language identifiers.
 fn detect_language(ext: &str) -> Option<&'static str> {
 	match ext {
-		"py" => Some("python"),
+		"py" | "ipynb" => Some("python"),
 		"cpp" | "cc" | "cxx" => Some("cpp"),
 		"c" => Some("c"),
 		"java" => Some("java"),
-		"js" => Some("javascript"),
-		"ts" => Some("typescript"),
+		"js" | "mjs" | "cjs" => Some("javascript"),
+		"ts" | "mts" | "cts" => Some("typescript"),
 		"rs" => Some("rust"),
 		"go" => Some("go"),
 		_ => None,
@@ -174,10 +175,42 @@ fn extract_archives(dir: &Path) -> Vec<PathBuf> {
 	created
 }
 
+/// Convert a notebook to `.scriptmark_extracted/notebooks/{stem}.py` next to it.
+///
+/// Returns the module path, or `None` (with a warning) if the notebook is unreadable.
+fn convert_notebook(dir: &Path, notebook: &Path) -> Option<PathBuf> {
+	let origin = notebook.file_name()?.to_string_lossy().into_owned();
+	let stem = notebook.file_stem()?.to_string_lossy().into_owned();
+	let module = std::fs::read_to_string(notebook)
+		.map_err(|e| e.to_string())
+		.and_then(|json| notebook::to_module(&json, &origin).map_err(|e| e.to_string()));
+	let module = match module {
+		Ok(m) => m,
+		Err(e) => {
+			eprintln!("[WARN] Skipping notebook {}: {e}", notebook.display());
+			return None;
+		}
+	};
+
+	let target_dir = dir.join(".scriptmark_extracted").join("notebooks");
+	let target = target_dir.join(format!("{stem}.py"));
+	if let Err(e) =
+		std::fs::create_dir_all(&target_dir).and_then(|_| std::fs::write(&target, module))
+	{
+		eprintln!(
+			"[WARN] Cannot write converted notebook {}: {e}",
+			target.display()
+		);
+		return None;
+	}
+	Some(target)
+}
+
 /// Scan directories for student submission files and group by student ID.
 ///
 /// Only includes files with recognized language extensions.
 /// If `extensions` is provided, only includes files matching those extensions.
+/// Notebooks (`.ipynb`) are converted to Python modules; see [`notebook`].
 pub fn discover_submissions(
 	paths: &[impl AsRef<Path>],
 	extensions: Option<&[&str]>,
@@ -258,6 +291,15 @@ pub fn discover_submissions(
 				None => continue,
 			};
 
+			let path = if ext == "ipynb" {
+				match convert_notebook(dir_path, &path) {
+					Some(module) => module,
+					None => continue,
+				}
+			} else {
+				path
+			};
+
 			by_student
 				.entry(sid)
 				.or_default()
@@ -329,6 +371,27 @@ mod tests {
 		assert!(result.by_student.contains_key("bob"));
 	}
 
+	#[test]
+	fn test_discover_converts_notebooks() {
+		let dir = tempfile::tempdir().unwrap();
+		std::fs::write(
+			dir.path().join("carol_Lab5.ipynb"),
+			r#"{"cells": [{"cell_type": "code", "source": ["!pip install x\n", "def foo(): return 42"]}]}"#,
+		)
+		.unwrap();
+
+		let result = discover_submissions(&[dir.path()], None).unwrap();
+		let files = &result.by_student["carol"];
+		assert_eq!(files[0].language, "python");
+		assert!(
+			files[0]
+				.path
+				.ends_with(".scriptmark_extracted/notebooks/carol_Lab5.py")
+		);
+		let module = std::fs::read_to_string(&files[0].path).unwrap();
+		assert!(module.contains("def foo(): return 42"));
+	}
+
 	#[test]
 	fn test_discover_submissions() {
 		let dir = tempfile::tempdir().unwrap();
@@ -27,6 +27,7 @@ pub fn display_summary(reports: &[&StudentReport], title: &str) {
 			TestStatus::Missing => ("MISSING", Color::DarkGrey),
 			TestStatus::Error => ("ERROR", Color::Red),
 			TestStatus::Timeout => ("TIMEOUT", Color::Yellow),
+			TestStatus::CompileError => ("COMPILE ERROR", Color::Red),
 		};
 
 		let grade_str = report
@@ -56,11 +57,17 @@ pub fn display_summary(reports: &[&StudentReport], title: &str) {
 	println!("{table}");
 }
 
-/// Display detailed failure reports for students with failures.
+/// Display detailed failure reports for students with failures, plus cases
+/// that passed through a fuzzy function-name match.
 pub fn display_failures(reports: &[&StudentReport]) {
+	let fuzzy = |r: &StudentReport| {
+		r.test_results
+			.iter()
+			.any(|t| t.cases.iter().any(|c| c.matched_name.is_some()))
+	};
 	let failed: Vec<_> = reports
 		.iter()
-		.filter(|r| r.status() == TestStatus::Failed || r.status() == TestStatus::Error)
+		.filter(|r| r.status() == TestStatus::Failed || r.status() == TestStatus::Error || fuzzy(r))
 		.collect();
 
 	if failed.is_empty() {
@@ -83,14 +90,17 @@ pub fn display_failures(reports: &[&StudentReport]) {
 
 		for test_result in &report.test_results {
 			for case in &test_result.cases {
-				if case.status == TestStatus::Passed {
+				if case.status == TestStatus::Passed && case.matched_name.is_none() {
 					continue;
 				}
 
 				let status_str = match case.status {
+					// Passed, but TAs should see what actually ran
+					TestStatus::Passed => "FUZZY".yellow().to_string(),
 					TestStatus::Failed => "FAIL".red().to_string(),
 					TestStatus::Error => "ERROR".red().bold().to_string(),
 					TestStatus::Timeout => "TIMEOUT".yellow().to_string(),
+					TestStatus::CompileError => "COMPILE".red().bold().to_string(),
 					_ => continue,
 				};
 
@@ -101,8 +111,17 @@ pub fn display_failures(reports: &[&StudentReport]) {
 					case.case_name
 				);
 
+				if let Some(name) = &case.matched_name {
+					println!("    {} {}", "called (fuzzy match):".dimmed(), name.yellow());
+				}
 				if let Some(failure) = &case.failure {
 					println!("    {}", failure.message.dimmed());
+					// Compiler diagnostics are the only clue for a build failure
+					if case.status == TestStatus::CompileError {
+						for line in failure.details.lines().take(10) {
+							println!("      {}", line.dimmed());
+						}
+					}
 				}
 
 				if let (Some(expected), Some(actual)) = (&case.expected, &case.actual) {
@@ -110,6 +110,7 @@ mod tests {
 				expected: None,
 				failure: None,
 				elapsed_ms: None,
+				matched_name: None,
 			})
 			.collect();
 
@@ -2,6 +2,7 @@ pub mod models;
 
 pub mod discovery;
 pub mod grading;
+pub mod notebook;
 pub mod roster;
 pub mod similarity;
 pub mod spec_loader;
@@ -9,8 +9,8 @@ use scriptmark::discovery::discover_submissions;
 use scriptmark::grading::apply_grading;
 use scriptmark::models::{FormulaPolicy, GradingPolicy, TemplatePolicy};
 use scriptmark::roster::load_roster;
+use scriptmark::runner::executor::ExecutorRegistry;
 use scriptmark::runner::orchestrator;
-use scriptmark::runner::python::PythonExecutor;
 use scriptmark::spec_loader::load_specs_from_dir;
 
 #[derive(Parser)]
@@ -332,11 +332,11 @@ async fn cmd_grade(args: GradeArgs) -> Result<()> {
 	println!("Loaded {} test specs", specs.len());
 
 	// 3. Run tests
-	let executor = PythonExecutor::with_python_cmd(&args.python);
+	let executors = ExecutorRegistry::builtin(&args.python);
 	let mut results = orchestrator::run_all(
 		&submissions,
 		&specs,
-		&executor,
+		&executors,
 		args.timeout,
 		args.concurrency,
 	)
@@ -485,11 +485,11 @@ async fn cmd_run(args: RunArgs) -> Result<()> {
 		load_specs_from_dir(&args.tests_dir).context("Failed to load test specifications")?;
 	println!("Loaded {} test specs", specs.len());
 
-	let executor = PythonExecutor::with_python_cmd(&args.python);
+	let executors = ExecutorRegistry::builtin(&args.python);
 	let results = orchestrator::run_all(
 		&submissions,
 		&specs,
-		&executor,
+		&executors,
 		args.timeout,
 		args.concurrency,
 	)
@@ -9,6 +9,8 @@ pub enum TestStatus {
 	Missing,
 	Error,
 	Timeout,
+	/// The submission did not compile; no case could run.
+	CompileError,
 }
 
 /// Detail about why a test case failed.
@@ -32,6 +34,10 @@ pub struct CaseResult {
 	pub failure: Option<FailureDetail>,
 	/// Execution time in milliseconds.
 	pub elapsed_ms: Option<u64>,
+	/// Function or class actually called when the requested name was matched
+	/// fuzzily (e.g. `find_min` for `find_max`). `None` for exact matches.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub matched_name: Option<String>,
 }
 
 /// Aggregated result for one test spec (one TOML file) for one student.
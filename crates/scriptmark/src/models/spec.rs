@@ -13,6 +13,28 @@ pub struct LintConfig {
 	pub weight: f64,
 }
 
+/// Static rules checked against the student's Python source with `ast`.
+/// Each rule is reported as its own scored case.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Requirements {
+	/// Functions or methods that must not be called, e.g. `"sorted"`, `"sort"`.
+	#[serde(default)]
+	pub forbidden_calls: Vec<String>,
+	/// Modules that must not be imported (submodules included).
+	#[serde(default)]
+	pub forbidden_imports: Vec<String>,
+	/// `ast` node types that must not appear, e.g. `"For"`, `"ListComp"`.
+	#[serde(default)]
+	pub forbidden_nodes: Vec<String>,
+	/// Functions that must call themselves.
+	#[serde(default)]
+	pub recursive: Vec<String>,
+	/// Maximum depth of nested `for`/`while`/`if`/`with`/`try`/`match`
+	/// blocks; `elif` does not add a level.
+	#[serde(default)]
+	pub max_nesting: Option<usize>,
+}
+
 fn default_max_warnings() -> usize {
 	10
 }
@@ -56,6 +78,16 @@ pub struct CheckSpec {
 	/// Path to WASM verifier module
 	#[serde(default)]
 	pub wasm: Option<String>,
+	/// Regex the stringified result (or stdout) must match
+	#[serde(default)]
+	pub regex: Option<String>,
+	/// Regex flags: `i` case-insensitive, `m` multiline, `s` dot matches
+	/// newline, `x` verbose
+	#[serde(default)]
+	pub flags: Option<String>,
+	/// Regex must match the whole output instead of any part of it
+	#[serde(default)]
+	pub full_match: bool,
 	/// Tolerance for approx checker
 	#[serde(default)]
 	pub tolerance: Option<f64>,
@@ -77,9 +109,13 @@ pub struct Oracle {
 	/// Built-in checker name (just verifies a property, no expected value).
 	#[serde(default)]
 	pub check: Option<String>,
-	/// Python script oracle.
+	/// Python script oracle: reads `{"args": [...], "named": {...}}` on stdin
+	/// and prints the expected value as JSON.
 	#[serde(default)]
 	pub python: Option<String>,
+	/// Seconds the Python oracle may run per case (default 10).
+	#[serde(default)]
+	pub timeout: Option<u64>,
 }
 
 /// Parametrize configuration — auto-generate test cases.
@@ -93,6 +129,11 @@ pub struct Parametrize {
 	/// Generator expressions per argument. Key = arg name, Value = generator string.
 	#[serde(default)]
 	pub args: std::collections::HashMap<String, String>,
+	/// Positional order of `args`, which is also the order they are generated
+	/// in: a generator may refer to arguments before it (`choice($nums)`,
+	/// `int(0, len(nums) - 1)`). Defaults to alphabetical.
+	#[serde(default)]
+	pub order: Vec<String>,
 	/// How to determine the expected output.
 	#[serde(default)]
 	pub oracle: Oracle,
@@ -108,10 +149,23 @@ pub struct TestCase {
 	pub id: Option<String>,
 
 	/// Arguments to pass to the function (for function-call tests).
-	/// May contain `$ref` strings referencing fixture results.
+	/// May contain `$ref` strings referencing fixture results, and tagged
+	/// values like `{ "$tuple" = [1, 2] }` (Python; see `TestCase::kwargs`).
 	#[serde(default)]
 	pub args: Vec<serde_json::Value>,
 
+	/// Keyword arguments (Python). Values may use the same tags as `args`:
+	/// `$tuple`, `$set`, `$frozenset`, `$bytes`, `$float` (`"inf"`, `"nan"`)
+	/// and `$none`.
+	#[serde(default)]
+	pub kwargs: std::collections::HashMap<String, serde_json::Value>,
+
+	/// Scripted responses to `input()` during a function call, in order.
+	/// Reading past the end raises `EOFError`. Node and Java read them as
+	/// stdin lines.
+	#[serde(default)]
+	pub inputs: Vec<String>,
+
 	/// Expected return value (for exact/approx matching).
 	#[serde(default)]
 	pub expect: Option<serde_json::Value>,
@@ -120,14 +174,50 @@ pub struct TestCase {
 	#[serde(default)]
 	pub expect_error: Option<String>,
 
+	/// Required type name of the return value (e.g. "tuple", "float"), as
+	/// reported by the language helper. Unchecked when omitted.
+	#[serde(default)]
+	pub expect_type: Option<String>,
+
 	/// Stdin input (for IO-based tests).
 	#[serde(default)]
 	pub stdin: Option<String>,
 
-	/// Expected stdout (for IO-based tests).
+	/// Expected stdout: the program's output for IO-based tests, or what the
+	/// function printed for function-call tests.
 	#[serde(default)]
 	pub expected_stdout: Option<String>,
 
+	/// Expected process exit code (for IO-based tests). Unchecked when omitted.
+	#[serde(default)]
+	pub expect_exit_code: Option<i32>,
+
+	/// Expected stderr (for IO-based tests). Unchecked when omitted.
+	#[serde(default)]
+	pub expected_stderr: Option<String>,
+
+	/// How to check stderr against `expected_stderr`. Defaults to exact match.
+	#[serde(default)]
+	pub stderr_check: Option<CheckMethod>,
+
+	/// Command-line arguments after the program (for IO-based tests).
+	#[serde(default)]
+	pub argv: Vec<String>,
+
+	/// Extra environment variables (for IO-based tests).
+	#[serde(default)]
+	pub env: std::collections::HashMap<String, String>,
+
+	/// Files placed in the case's fresh working directory (for IO-based
+	/// tests). Key = relative path, value = inline content or `{ source = ... }`.
+	#[serde(default)]
+	pub files: std::collections::HashMap<String, CaseFile>,
+
+	/// Files the program must leave in its working directory. Key = relative
+	/// path, value = expected content or `{ expect = ..., check = ... }`.
+	#[serde(default)]
+	pub expect_files: std::collections::HashMap<String, ExpectFile>,
+
 	/// How to check the result. Defaults to exact match.
 	#[serde(default)]
 	pub check: Option<CheckMethod>,
@@ -144,6 +234,89 @@ pub struct TestCase {
 	/// cases may call different functions).
 	#[serde(default)]
 	pub function: Option<String>,
+
+	/// Class to construct for an object-oriented case (chain mode). Each of
+	/// `calls` then runs on the same instance and is reported as a sub-case.
+	#[serde(default)]
+	pub class: Option<String>,
+
+	/// Constructor arguments for `class`. May contain `$ref` strings.
+	#[serde(default)]
+	pub init_args: Vec<serde_json::Value>,
+
+	/// Method calls made in order on the `class` instance.
+	#[serde(default)]
+	pub calls: Vec<MethodCall>,
+}
+
+/// One method call in an object-oriented case.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MethodCall {
+	/// Method name on the instance.
+	pub method: String,
+
+	/// Sub-case name. Defaults to `"<n>. <method>"`.
+	#[serde(default)]
+	pub name: Option<String>,
+
+	/// Arguments to the method. May contain `$ref` strings.
+	#[serde(default)]
+	pub args: Vec<serde_json::Value>,
+
+	/// Keyword arguments to the method.
+	#[serde(default)]
+	pub kwargs: std::collections::HashMap<String, serde_json::Value>,
+
+	/// Expected return value. Unchecked when omitted (the call just has to
+	/// not raise), unless `check` is set.
+	#[serde(default)]
+	pub expect: Option<serde_json::Value>,
+
+	/// Expected error type (e.g. "IndexError").
+	#[serde(default)]
+	pub expect_error: Option<String>,
+
+	/// Required type name of the return value.
+	#[serde(default)]
+	pub expect_type: Option<String>,
+
+	/// How to check the return value. Defaults to exact match.
+	#[serde(default)]
+	pub check: Option<CheckMethod>,
+}
+
+impl MethodCall {
+	/// Name of the sub-case for the `index`-th (0-based) call.
+	pub fn step_name(&self, index: usize) -> String {
+		self.name
+			.clone()
+			.unwrap_or_else(|| format!("{}. {}", index + 1, self.method))
+	}
+}
+
+/// A file materialised in an IO case's working directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CaseFile {
+	/// Inline text content.
+	Inline(String),
+	/// Copied from a path relative to the spec file.
+	Copy { source: String },
+}
+
+/// Expectation for a file written by the student program.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ExpectFile {
+	/// Exact expected content.
+	Content(String),
+	/// Content checked by a checker (exact match when `check` is omitted).
+	Checked {
+		#[serde(default)]
+		expect: Option<String>,
+		#[serde(default)]
+		check: Option<Box<CheckMethod>>,
+	},
 }
 
 /// Metadata for a test spec file.
@@ -162,10 +335,16 @@ pub struct TestMeta {
 	pub function: Option<String>,
 
 	/// Compile command template (for compiled languages).
-	/// Placeholders: {source}, {output}
+	/// Placeholders: {source}, {output}. Defaults per language
+	/// (e.g. `gcc -O2 -std=c11 -o {output} {source} -lm` for C).
 	#[serde(default)]
 	pub compile: Option<String>,
 
+	/// Class holding `main` for IO cases and searched first for static
+	/// methods (Java). Detected from the source when omitted.
+	#[serde(default)]
+	pub main_class: Option<String>,
+
 	/// Teacher module paths — loaded before student code in chain mode.
 	/// Exports (public names) populate the `$ref` context as live Python objects.
 	#[serde(default)]
@@ -180,6 +359,11 @@ pub struct TestMeta {
 	/// e.g. `["numpy", "pandas"]`. By default only safe stdlib modules are allowed.
 	#[serde(default)]
 	pub allowed_imports: Vec<String>,
+
+	/// Fail cases whose function or class was only found by fuzzy name
+	/// matching, instead of just reporting the substitution.
+	#[serde(default)]
+	pub strict_names: bool,
 }
 
 fn default_copy_refs() -> bool {
@@ -220,4 +404,7 @@ pub struct TestSpec {
 	/// Optional lint-based style scoring.
 	#[serde(default)]
 	pub lint: Option<LintConfig>,
+	/// Static code requirements (Python), reported as scored cases.
+	#[serde(default)]
+	pub requirements: Option<Requirements>,
 }
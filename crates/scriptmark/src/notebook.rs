@@ -0,0 +1,327 @@
+//! Jupyter notebook (`.ipynb`) support.
+//!
+//! Code cells are concatenated into a synthetic Python module. IPython-only
+//! lines (magics, `!` shell escapes, `?` help) become `pass` so every kept
+//! line stays at the same offset within its cell, and each cell is preceded
+//! by a `# %% [cell N]` marker. [`CellMap`] reads those markers back to turn
+//! module line numbers into notebook cell positions.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+
+/// First line of every converted module; carries the notebook's file name.
+const HEADER_PREFIX: &str = "# scriptmark: converted from notebook ";
+const CELL_MARKER_PREFIX: &str = "# %% [cell ";
+
+/// Cell magics whose body is still plain Python.
+const PYTHON_CELL_MAGICS: &[&str] = &["time", "timeit", "capture"];
+
+/// Stand-in for IPython's `display()` so notebooks that call it still import.
+const PRELUDE: &str = "def display(*args, **kwargs):\n    pass\n";
+
+static SHELL_ASSIGN_RE: LazyLock<Regex> =
+	LazyLock::new(|| Regex::new(r"^[A-Za-z_]\w*\s*=\s*[!%]").unwrap());
+static HELP_RE: LazyLock<Regex> =
+	LazyLock::new(|| Regex::new(r"^\??[A-Za-z_][\w.]*\?{1,2}$").unwrap());
+static LINE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bline (\d+)").unwrap());
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotebookError {
+	#[error("invalid notebook JSON: {0}")]
+	Json(#[from] serde_json::Error),
+	#[error("not a notebook: no `cells` array")]
+	NoCells,
+}
+
+/// Convert notebook JSON into a Python module.
+///
+/// `origin` is the notebook's file name, recorded in the header so failure
+/// messages can name it. Cell numbers are 1-based and count every cell
+/// (markdown included), matching what a student sees scrolling the notebook.
+pub fn to_module(notebook_json: &str, origin: &str) -> Result<String, NotebookError> {
+	let nb: serde_json::Value = serde_json::from_str(notebook_json)?;
+
+	// nbformat 4 has top-level `cells`; nbformat 3 nests them in worksheets
+	let cells: Vec<&serde_json::Value> = match nb.get("cells").and_then(|c| c.as_array()) {
+		Some(cells) => cells.iter().collect(),
+		None => nb
+			.get("worksheets")
+			.and_then(|w| w.as_array())
+			.ok_or(NotebookError::NoCells)?
+			.iter()
+			.filter_map(|w| w.get("cells").and_then(|c| c.as_array()))
+			.flatten()
+			.collect(),
+	};
+
+	let mut module = format!("{HEADER_PREFIX}{origin}\n{PRELUDE}");
+	for (idx, cell) in cells.iter().enumerate() {
+		if cell.get("cell_type").and_then(|t| t.as_str()) != Some("code") {
+			continue;
+		}
+		let source = cell_source(cell);
+		if source.trim().is_empty() {
+			continue;
+		}
+		module.push_str(&format!("\n{CELL_MARKER_PREFIX}{}]\n", idx + 1));
+		for line in convert_cell(&source) {
+			module.push_str(&line);
+			module.push('\n');
+		}
+	}
+	Ok(module)
+}
+
+/// Cell source, stored either as one string or a list of lines.
+fn cell_source(cell: &serde_json::Value) -> String {
+	let source = cell.get("source").or_else(|| cell.get("input"));
+	match source {
+		Some(serde_json::Value::String(s)) => s.clone(),
+		Some(serde_json::Value::Array(lines)) => lines.iter().filter_map(|l| l.as_str()).collect(),
+		_ => String::new(),
+	}
+}
+
+/// Rewrite one cell line by line, keeping the line count unchanged.
+fn convert_cell(source: &str) -> Vec<String> {
+	let lines: Vec<&str> = source.lines().collect();
+
+	if let Some(first) = lines.first()
+		&& let Some(magic) = first.trim_start().strip_prefix("%%")
+	{
+		let name = magic.split_whitespace().next().unwrap_or("");
+		if PYTHON_CELL_MAGICS.contains(&name) {
+			let mut out = vec![format!("pass  # dropped: {first}")];
+			out.extend(convert_lines(&lines[1..]));
+			return out;
+		}
+		// Any other cell magic (%%bash, %%html, ...) isn't Python at all
+		return lines.iter().map(|l| format!("# dropped: {l}")).collect();
+	}
+
+	convert_lines(&lines)
+}
+
+/// Rewrite Python lines, dropping IPython-only syntax where a logical line
+/// starts. Lines inside brackets or triple-quoted strings, or after a `\`,
+/// continue a statement (`% b`, `!= b`) and are kept.
+fn convert_lines(lines: &[&str]) -> Vec<String> {
+	let mut lexer = LineLexer::default();
+	lines
+		.iter()
+		.map(|line| {
+			if lexer.at_statement_start()
+				&& let Some(dropped) = drop_ipython_line(line)
+			{
+				return dropped;
+			}
+			lexer.scan(line);
+			line.to_string()
+		})
+		.collect()
+}
+
+/// `line` replaced by `pass`, if it is a magic, shell escape or help query.
+fn drop_ipython_line(line: &str) -> Option<String> {
+	let trimmed = line.trim_start();
+	let ipython_only = trimmed.starts_with('%')
+		|| trimmed.starts_with('!')
+		|| SHELL_ASSIGN_RE.is_match(trimmed)
+		|| HELP_RE.is_match(trimmed.trim_end());
+	ipython_only.then(|| {
+		let indent = &line[..line.len() - trimmed.len()];
+		format!("{indent}pass  # dropped: {trimmed}")
+	})
+}
+
+/// Just enough of Python's tokenizer to tell whether the next line starts a
+/// new statement.
+#[derive(Default)]
+struct LineLexer {
+	/// Open `(`, `[` and `{`.
+	depth: usize,
+	/// Open string: its quote and whether it is triple-quoted.
+	string: Option<(char, bool)>,
+	/// The last line ended with a `\` continuation.
+	continued: bool,
+}
+
+impl LineLexer {
+	fn at_statement_start(&self) -> bool {
+		self.depth == 0 && self.string.is_none() && !self.continued
+	}
+
+	/// Advance past one line.
+	fn scan(&mut self, line: &str) {
+		let chars: Vec<char> = line.chars().collect();
+		let mut comment = false;
+		let mut i = 0;
+		while i < chars.len() {
+			let c = chars[i];
+			if let Some((quote, triple)) = self.string {
+				if c == '\\' {
+					i += 2;
+					continue;
+				}
+				if triple && chars[i..].starts_with(&[quote; 3]) {
+					self.string = None;
+					i += 3;
+					continue;
+				}
+				if !triple && c == quote {
+					self.string = None;
+				}
+				i += 1;
+				continue;
+			}
+			match c {
+				'#' => {
+					comment = true;
+					break;
+				}
+				'\'' | '"' if chars[i..].starts_with(&[c; 3]) => {
+					self.string = Some((c, true));
+					i += 3;
+					continue;
+				}
+				'\'' | '"' => self.string = Some((c, false)),
+				'(' | '[' | '{' => self.depth += 1,
+				')' | ']' | '}' => self.depth = self.depth.saturating_sub(1),
+				_ => {}
+			}
+			i += 1;
+		}
+		self.continued = !comment && line.ends_with('\\');
+		// A single-quoted string only spans lines through a `\` continuation
+		if let Some((_, false)) = self.string
+			&& !self.continued
+		{
+			self.string = None;
+		}
+	}
+}
+
+/// Maps line numbers of a converted module back to notebook cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellMap {
+	/// Notebook file name the module was converted from.
+	pub origin: String,
+	/// `(first module line, cell number)`, in module order.
+	cells: Vec<(usize, usize)>,
+}
+
+impl CellMap {
+	/// Rebuild the map from a converted module. `None` for ordinary Python.
+	pub fn from_module(source: &str) -> Option<Self> {
+		let mut lines = source.lines();
+		let origin = lines.next()?.strip_prefix(HEADER_PREFIX)?.to_string();
+		let cells = lines
+			.enumerate()
+			.filter_map(|(i, line)| {
+				let cell = line.strip_prefix(CELL_MARKER_PREFIX)?.strip_suffix(']')?;
+				// +3: 1-based numbering, the header line consumed above, and the
+				// marker line itself
+				Some((i + 3, cell.parse().ok()?))
+			})
+			.collect();
+		Some(Self { origin, cells })
+	}
+
+	/// Read a file and build its map, if it is a converted notebook.
+	pub fn from_path(path: &Path) -> Option<Self> {
+		Self::from_module(&std::fs::read_to_string(path).ok()?)
+	}
+
+	/// `(cell number, line within cell)` for a 1-based module line.
+	pub fn locate(&self, line: usize) -> Option<(usize, usize)> {
+		self.cells
+			.iter()
+			.rev()
+			.find(|(start, _)| *start <= line)
+			.map(|(start, cell)| (*cell, line - start + 1))
+	}
+
+	/// Replace `line N` references in `text` with `cell C, line L`, and the
+	/// converted module's path with the notebook name.
+	pub fn rewrite(&self, text: &str, module_path: &Path) -> String {
+		let text = LINE_RE.replace_all(text, |caps: &Captures| {
+			match caps[1].parse().ok().and_then(|n| self.locate(n)) {
+				Some((cell, line)) => format!("cell {cell}, line {line}"),
+				None => caps[0].to_string(),
+			}
+		});
+		text.replace(&*module_path.to_string_lossy(), &self.origin)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const NOTEBOOK: &str = r##"{
+  "nbformat": 4,
+  "cells": [
+    {"cell_type": "markdown", "source": ["# Lab 5"]},
+    {"cell_type": "code", "source": ["%matplotlib inline\n", "!pip install numpy\n", "import math"]},
+    {"cell_type": "code", "source": "def area(r):\n    files = !ls\n    return math.pi * r ** 2\n"},
+    {"cell_type": "code", "source": ["%%bash\n", "echo hi"]},
+    {"cell_type": "code", "source": ["area?\n", "print(area(1))"]}
+  ]
+}"##;
+
+	#[test]
+	fn test_magics_and_shell_escapes_are_dropped() {
+		let module = to_module(NOTEBOOK, "alice_Lab5.ipynb").unwrap();
+		assert!(module.starts_with("# scriptmark: converted from notebook alice_Lab5.ipynb\n"));
+		assert!(module.contains("pass  # dropped: %matplotlib inline"));
+		assert!(module.contains("pass  # dropped: !pip install numpy"));
+		assert!(module.contains("    pass  # dropped: files = !ls"));
+		assert!(module.contains("# dropped: echo hi"));
+		assert!(module.contains("pass  # dropped: area?"));
+		assert!(!module.contains("# Lab 5"));
+	}
+
+	#[test]
+	fn test_continuation_lines_are_kept() {
+		let lines = [
+			"ok = (a",
+			"      != b)",
+			"rest = [a",
+			"        % b]",
+			"doc = \"\"\"",
+			"%not a magic",
+			"!not a shell escape",
+			"\"\"\"",
+			"s = '(' + \"'''\"  # (",
+			"wrapped = a \\",
+			"    % b",
+			"%who",
+		];
+		let mut expected: Vec<String> = lines[..11].iter().map(|l| l.to_string()).collect();
+		expected.push("pass  # dropped: %who".to_string());
+		assert_eq!(convert_lines(&lines), expected);
+	}
+
+	#[test]
+	fn test_cell_map_locates_lines() {
+		let module = to_module(NOTEBOOK, "alice_Lab5.ipynb").unwrap();
+		let map = CellMap::from_module(&module).unwrap();
+		let line_of = |needle: &str| module.lines().position(|l| l.contains(needle)).unwrap() + 1;
+
+		assert_eq!(map.locate(line_of("return math.pi")), Some((3, 3)));
+		assert_eq!(map.locate(line_of("print(area(1))")), Some((5, 2)));
+
+		let path = Path::new("/tmp/nb/alice_Lab5.py");
+		let msg = format!(
+			"File \"/tmp/nb/alice_Lab5.py\", line {}, in area",
+			line_of("return math.pi")
+		);
+		assert_eq!(
+			map.rewrite(&msg, path),
+			"File \"alice_Lab5.ipynb\", cell 3, line 3, in area"
+		);
+		assert!(CellMap::from_module("import math\n").is_none());
+	}
+}
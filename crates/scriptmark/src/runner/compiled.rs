@@ -0,0 +1,630 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use tokio::sync::OnceCell;
+
+use crate::models::{CaseResult, FailureDetail, StudentFile, TestCase, TestSpec, TestStatus};
+use crate::runner::evaluate::{check_expected_files, evaluate_io_output};
+use crate::runner::executor::{BoxFuture, Executor};
+use crate::runner::process::{
+	CaseDir, SpawnError, absolute_path, find_student_file, missing_file_result,
+	resolve_program_path, sandboxed_command, setup_error_result, spawn_with_timeout,
+};
+use crate::runner::sandbox::SandboxConfig;
+
+/// Why a build produced nothing runnable.
+#[derive(Debug, Clone)]
+pub(crate) struct BuildFailure {
+	pub status: TestStatus,
+	pub message: String,
+	pub details: String,
+}
+
+impl BuildFailure {
+	fn error(message: impl Into<String>) -> Self {
+		Self {
+			status: TestStatus::Error,
+			message: message.into(),
+			details: String::new(),
+		}
+	}
+
+	/// Report the build failure as the result of `case`.
+	pub fn case_result(&self, case: &TestCase) -> CaseResult {
+		CaseResult {
+			case_name: case.name.clone(),
+			status: self.status,
+			actual: None,
+			expected: case.expected_stdout.clone(),
+			failure: Some(FailureDetail {
+				message: self.message.clone(),
+				details: self.details.clone(),
+			}),
+			elapsed_ms: Some(0),
+			matched_name: None,
+		}
+	}
+}
+
+type BuildCell<T> = Arc<OnceCell<Result<T, BuildFailure>>>;
+
+static BUILD_ROOT_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Per-executor cache of build products, keyed by (source file, build key).
+///
+/// Each build gets a fresh scratch directory under a private temp root,
+/// which is removed when the cache is dropped.
+pub(crate) struct BuildCache<T> {
+	root: PathBuf,
+	next_build: AtomicUsize,
+	builds: Mutex<HashMap<(PathBuf, String), BuildCell<T>>>,
+}
+
+impl<T: Clone> BuildCache<T> {
+	pub fn new() -> Self {
+		Self {
+			root: std::env::temp_dir().join(format!(
+				"scriptmark-build-{}-{}",
+				std::process::id(),
+				BUILD_ROOT_COUNTER.fetch_add(1, Ordering::Relaxed)
+			)),
+			next_build: AtomicUsize::new(0),
+			builds: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Return the cached product for `(source, key)`, running `build` in a
+	/// fresh scratch directory the first time. Concurrent callers wait for
+	/// the same build.
+	pub async fn get_or_build<F, Fut>(
+		&self,
+		source: &Path,
+		key: &str,
+		build: F,
+	) -> Result<T, BuildFailure>
+	where
+		F: FnOnce(PathBuf) -> Fut,
+		Fut: Future<Output = Result<T, BuildFailure>>,
+	{
+		let cell = {
+			let mut builds = self.builds.lock().unwrap();
+			builds
+				.entry((source.to_path_buf(), key.to_string()))
+				.or_default()
+				.clone()
+		};
+		cell.get_or_init(|| async {
+			let build_dir = self
+				.root
+				.join(self.next_build.fetch_add(1, Ordering::Relaxed).to_string());
+			std::fs::create_dir_all(&build_dir).map_err(|e| {
+				BuildFailure::error(format!(
+					"Failed to create build dir {}: {e}",
+					build_dir.display()
+				))
+			})?;
+			build(build_dir).await
+		})
+		.await
+		.clone()
+	}
+
+	#[cfg(test)]
+	fn len(&self) -> usize {
+		self.builds.lock().unwrap().len()
+	}
+}
+
+impl<T> Drop for BuildCache<T> {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_dir_all(&self.root);
+	}
+}
+
+/// Resource limits for compilers, which need far more address space and
+/// processes than student code.
+pub(crate) fn build_sandbox() -> SandboxConfig {
+	SandboxConfig {
+		cpu_secs: 120,
+		mem_mb: 4096,
+		fsize_mb: 256,
+		nofile: 256,
+		nproc: 256,
+	}
+}
+
+/// Run a build command (`argv[0]` resolved on `PATH`) inside `build_dir`.
+///
+/// A non-zero exit or timeout is a `CompileError` carrying the compiler
+/// output; a compiler that cannot be spawned is a plain `Error`.
+pub(crate) async fn run_build(
+	argv: &[String],
+	build_dir: &Path,
+	env: &[(String, String)],
+	sandbox: &SandboxConfig,
+	timeout_secs: u64,
+) -> Result<(), BuildFailure> {
+	let Some((program, args)) = argv.split_first() else {
+		return Err(BuildFailure::error("empty compile command"));
+	};
+
+	let mut cmd = sandboxed_command(resolve_program_path(program), sandbox);
+	cmd.args(args)
+		.envs(env.iter().map(|(k, v)| (k, v)))
+		.current_dir(build_dir);
+
+	match spawn_with_timeout(cmd, None, timeout_secs).await {
+		Ok(out) if out.status.success() => Ok(()),
+		Ok(out) => {
+			let stdout = String::from_utf8_lossy(&out.stdout);
+			let stderr = String::from_utf8_lossy(&out.stderr);
+			Err(BuildFailure {
+				status: TestStatus::CompileError,
+				message: format!("Compilation failed ({})", out.status),
+				details: format!("{stdout}{stderr}").trim_end().to_string(),
+			})
+		}
+		Err(SpawnError::Timeout) => Err(BuildFailure {
+			status: TestStatus::CompileError,
+			message: format!("Compilation timed out after {timeout_secs}s"),
+			details: String::new(),
+		}),
+		Err(SpawnError::Spawn(e)) => Err(BuildFailure::error(format!(
+			"Failed to spawn compiler '{program}': {e}"
+		))),
+	}
+}
+
+/// Expand a compile template into argv, substituting `{source}` and `{output}`.
+fn expand_template(template: &str, source: &Path, output: &Path) -> Vec<String> {
+	let source = source.to_string_lossy();
+	let output = output.to_string_lossy();
+	template
+		.split_whitespace()
+		.map(|p| p.replace("{source}", &source).replace("{output}", &output))
+		.collect()
+}
+
+/// Executor for languages that compile one source file into a native binary
+/// (C, C++, Rust, Go).
+///
+/// The compile command comes from `TestMeta.compile` (placeholders `{source}`
+/// and `{output}`), falling back to a per-language default. Each student file
+/// is built once in its own scratch directory and the binary is reused for
+/// every IO case of every spec that shares the same command.
+pub struct CompiledExecutor {
+	language: String,
+	compile_template: String,
+	compile_timeout_secs: u64,
+	build_env: Vec<(String, String)>,
+	build_failure_status: TestStatus,
+	sandbox: SandboxConfig,
+	builds: BuildCache<PathBuf>,
+	python_cmd: String,
+}
+
+impl CompiledExecutor {
+	/// Executor for a language with the given default compile template.
+	pub fn new(language: impl Into<String>, compile_template: impl Into<String>) -> Self {
+		Self {
+			language: language.into(),
+			compile_template: compile_template.into(),
+			compile_timeout_secs: 60,
+			build_env: Vec::new(),
+			build_failure_status: TestStatus::CompileError,
+			sandbox: SandboxConfig::default(),
+			builds: BuildCache::new(),
+			python_cmd: resolve_program_path("python3"),
+		}
+	}
+
+	/// C executor (`gcc`).
+	pub fn c() -> Self {
+		Self::new("c", "gcc -O2 -std=c11 -o {output} {source} -lm")
+	}
+
+	/// C++ executor (`g++`).
+	pub fn cpp() -> Self {
+		Self::new("cpp", "g++ -O2 -std=c++17 -o {output} {source}")
+	}
+
+	/// Rust executor (`rustc`, no Cargo, std only).
+	pub fn rust() -> Self {
+		// The rustup proxy needs to find its toolchains after env_clear()
+		let mut executor = Self::new("rust", "rustc --edition 2021 -O -o {output} {source}")
+			.with_build_failure_status(TestStatus::Error);
+		let home = std::env::var_os("HOME").map(PathBuf::from);
+		for (key, fallback) in [("RUSTUP_HOME", ".rustup"), ("CARGO_HOME", ".cargo")] {
+			if let Some(value) = std::env::var_os(key)
+				.map(PathBuf::from)
+				.or_else(|| home.as_ref().map(|h| h.join(fallback)))
+			{
+				executor = executor.with_build_env(key, value.to_string_lossy());
+			}
+		}
+		if let Ok(toolchain) = std::env::var("RUSTUP_TOOLCHAIN") {
+			executor = executor.with_build_env("RUSTUP_TOOLCHAIN", toolchain);
+		}
+		executor
+	}
+
+	/// Go executor (`go build`, offline, standard library only).
+	pub fn go() -> Self {
+		// Share one build cache so the standard library is compiled only once
+		let cache = std::env::var("GOCACHE").unwrap_or_else(|_| {
+			std::env::temp_dir()
+				.join("scriptmark-go-cache")
+				.to_string_lossy()
+				.into_owned()
+		});
+		let mut executor = Self::new("go", "go build -o {output} {source}")
+			.with_build_env("GOCACHE", cache)
+			.with_build_env("GOPROXY", "off")
+			.with_build_env("GOTOOLCHAIN", "local")
+			.with_build_env("CGO_ENABLED", "0")
+			.with_build_failure_status(TestStatus::Error);
+		if let Ok(root) = std::env::var("GOROOT") {
+			executor = executor.with_build_env("GOROOT", root);
+		}
+		executor
+	}
+
+	/// Extra environment variable for the compiler (the sandbox clears the rest).
+	pub fn with_build_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+		self.build_env.push((key.into(), value.into()));
+		self
+	}
+
+	/// Status reported when the compiler rejects a submission.
+	/// Default: `CompileError`.
+	pub fn with_build_failure_status(mut self, status: TestStatus) -> Self {
+		self.build_failure_status = status;
+		self
+	}
+
+	/// Timeout for a single compilation, in seconds. Default: 60.
+	pub fn with_compile_timeout(mut self, secs: u64) -> Self {
+		self.compile_timeout_secs = secs;
+		self
+	}
+
+	/// Python interpreter for Python verifier checks. Default: `python3`.
+	pub fn with_python_cmd(mut self, python_cmd: impl Into<String>) -> Self {
+		self.python_cmd = resolve_program_path(&python_cmd.into());
+		self
+	}
+
+	/// Compile once per (source file, command), returning the cached binary path.
+	async fn build(&self, source: &Path, template: &str) -> Result<PathBuf, BuildFailure> {
+		self.builds
+			.get_or_build(source, template, |build_dir| async move {
+				let stem = source
+					.file_stem()
+					.and_then(|s| s.to_str())
+					.unwrap_or("main");
+				let output = build_dir.join(stem);
+				let argv = expand_template(template, source, &output);
+				run_build(
+					&argv,
+					&build_dir,
+					&self.build_env,
+					&build_sandbox(),
+					self.compile_timeout_secs,
+				)
+				.await?;
+				if output.is_file() {
+					Ok(output)
+				} else {
+					Err(BuildFailure {
+						status: TestStatus::CompileError,
+						message: format!(
+							"Compiler produced no binary at {}; does the command use {{output}}?",
+							output.display()
+						),
+						details: String::new(),
+					})
+				}
+			})
+			.await
+			.map_err(|mut failure| {
+				if failure.status == TestStatus::CompileError {
+					failure.status = self.build_failure_status;
+				}
+				failure
+			})
+	}
+
+	async fn run_case(
+		&self,
+		student_files: &[StudentFile],
+		spec: &TestSpec,
+		case: &TestCase,
+		timeout_secs: u64,
+	) -> CaseResult {
+		let Some(student_file) = find_student_file(student_files, &spec.meta.file, None) else {
+			return missing_file_result(case, &spec.meta.file);
+		};
+
+		if spec.meta.function.is_some() {
+			return CaseResult {
+				case_name: case.name.clone(),
+				status: TestStatus::Error,
+				actual: None,
+				expected: None,
+				failure: Some(FailureDetail {
+					message: format!(
+						"Function-call cases are not supported for {}; use stdin/expected_stdout",
+						self.language
+					),
+					details: String::new(),
+				}),
+				elapsed_ms: Some(0),
+				matched_name: None,
+			};
+		}
+
+		let template = spec
+			.meta
+			.compile
+			.as_deref()
+			.unwrap_or(&self.compile_template);
+		// The compiler runs in the build dir, so a relative submission path
+		// would not resolve there
+		let source = absolute_path(&student_file.path);
+		let binary = match self.build(&source, template).await {
+			Ok(b) => b,
+			Err(failure) => return failure.case_result(case),
+		};
+
+		let start = Instant::now();
+		let mut cmd = sandboxed_command(&binary, &self.sandbox);
+		let case_dir = match CaseDir::new(case) {
+			Ok(dir) => dir,
+			Err(e) => return setup_error_result(case, e),
+		};
+		case_dir.apply_io(&mut cmd, case);
+		let result = spawn_with_timeout(cmd, case.stdin.as_deref(), timeout_secs).await;
+		let result = evaluate_io_output(result, case, timeout_secs, start, &self.python_cmd);
+		check_expected_files(result, case, case_dir.path(), &self.python_cmd)
+	}
+}
+
+impl Executor for CompiledExecutor {
+	fn language(&self) -> &str {
+		&self.language
+	}
+
+	fn python_cmd(&self) -> &str {
+		&self.python_cmd
+	}
+
+	fn execute_case<'a>(
+		&'a self,
+		student_files: &'a [StudentFile],
+		spec: &'a TestSpec,
+		case: &'a TestCase,
+		timeout_secs: u64,
+	) -> BoxFuture<'a, CaseResult> {
+		Box::pin(self.run_case(student_files, spec, case, timeout_secs))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn spec(toml_str: &str) -> TestSpec {
+		toml::from_str(toml_str).unwrap()
+	}
+
+	fn c_file(dir: &Path, name: &str, code: &str) -> Vec<StudentFile> {
+		let path = dir.join(name);
+		std::fs::write(&path, code).unwrap();
+		vec![StudentFile {
+			path,
+			language: "c".to_string(),
+		}]
+	}
+
+	/// Scratch dir under the current directory, for submissions given by a
+	/// relative path (see [`relative`]).
+	fn relative_dir() -> tempfile::TempDir {
+		tempfile::tempdir_in(".").unwrap()
+	}
+
+	/// `files` as paths relative to the current directory, the way discovery
+	/// reports a submissions dir given relatively on the command line.
+	fn relative(mut files: Vec<StudentFile>) -> Vec<StudentFile> {
+		let cwd = std::env::current_dir().unwrap();
+		for file in &mut files {
+			file.path = file.path.strip_prefix(&cwd).unwrap().to_path_buf();
+		}
+		files
+	}
+
+	const ECHO_SPEC: &str = r#"
+[meta]
+name = "double"
+file = "double.c"
+language = "c"
+
+[[cases]]
+name = "21"
+stdin = "21\n"
+expected_stdout = "42\n"
+
+[[cases]]
+name = "0"
+stdin = "0\n"
+expected_stdout = "0\n"
+"#;
+
+	#[tokio::test]
+	async fn test_compiles_once_and_runs_io_cases() {
+		let dir = tempfile::tempdir().unwrap();
+		let files = c_file(
+			dir.path(),
+			"alice_double.c",
+			"#include <stdio.h>\nint main(void){int n;scanf(\"%d\",&n);printf(\"%d\\n\",n*2);return 0;}\n",
+		);
+		let spec = spec(ECHO_SPEC);
+		let executor = CompiledExecutor::c();
+
+		let results = executor.execute_chain(&files, &spec, &spec.cases, 5).await;
+		assert!(results.iter().all(|r| r.status == TestStatus::Passed));
+		assert_eq!(executor.builds.len(), 1);
+	}
+
+	#[tokio::test]
+	async fn test_relative_submission_path_builds() {
+		let dir = relative_dir();
+		let files = relative(c_file(
+			dir.path(),
+			"alice_double.c",
+			"#include <stdio.h>\nint main(void){int n;scanf(\"%d\",&n);printf(\"%d\\n\",n*2);return 0;}\n",
+		));
+		let spec = spec(ECHO_SPEC);
+
+		let results = CompiledExecutor::c()
+			.execute_chain(&files, &spec, &spec.cases, 5)
+			.await;
+		for r in &results {
+			assert_eq!(r.status, TestStatus::Passed, "{r:?}");
+		}
+	}
+
+	#[tokio::test]
+	async fn test_compile_error_is_reported_per_case() {
+		let dir = tempfile::tempdir().unwrap();
+		let files = c_file(
+			dir.path(),
+			"bob_double.c",
+			"int main(void) { return oops; }\n",
+		);
+		let spec = spec(ECHO_SPEC);
+		let executor = CompiledExecutor::c();
+
+		let results = executor.execute_chain(&files, &spec, &spec.cases, 5).await;
+		for r in &results {
+			assert_eq!(r.status, TestStatus::CompileError);
+			assert!(r.failure.as_ref().unwrap().details.contains("oops"));
+		}
+	}
+
+	#[tokio::test]
+	async fn test_spec_compile_template_overrides_default() {
+		let dir = tempfile::tempdir().unwrap();
+		let files = c_file(
+			dir.path(),
+			"carol_double.c",
+			"#include <stdio.h>\nint main(void){printf(\"%d\\n\", VALUE);return 0;}\n",
+		);
+		let mut spec = spec(ECHO_SPEC);
+		spec.meta.compile = Some("gcc -DVALUE=42 -o {output} {source}".to_string());
+		let executor = CompiledExecutor::c();
+
+		let result = executor
+			.execute_case(&files, &spec, &spec.cases[0], 5)
+			.await;
+		assert_eq!(result.status, TestStatus::Passed);
+	}
+
+	#[tokio::test]
+	async fn test_rust_builds_with_rustc() {
+		let dir = relative_dir();
+		let mut files = relative(c_file(
+			dir.path(),
+			"dave_double.rs",
+			"use std::io::Read;\nfn main() {\n    let mut s = String::new();\n    std::io::stdin().read_to_string(&mut s).unwrap();\n    let n: i64 = s.trim().parse().unwrap();\n    println!(\"{}\", n * 2);\n}\n",
+		));
+		files[0].language = "rust".to_string();
+		let mut spec = spec(ECHO_SPEC);
+		spec.meta.file = "double.rs".to_string();
+		spec.meta.language = "rust".to_string();
+		let executor = CompiledExecutor::rust();
+
+		let results = executor.execute_chain(&files, &spec, &spec.cases, 5).await;
+		for r in &results {
+			assert_eq!(r.status, TestStatus::Passed, "{r:?}");
+		}
+
+		let files = c_file(
+			dir.path(),
+			"erin_double.rs",
+			"fn main() { let x: u8 = \"no\"; }\n",
+		);
+		let result = executor
+			.execute_case(&files, &spec, &spec.cases[0], 5)
+			.await;
+		assert_eq!(result.status, TestStatus::Error);
+		assert!(result.failure.unwrap().details.contains("mismatched types"));
+	}
+
+	#[tokio::test]
+	async fn test_go_builds_with_go_build() {
+		if std::process::Command::new("go")
+			.arg("version")
+			.output()
+			.is_err()
+		{
+			eprintln!("skipping: `go` is not on PATH");
+			return;
+		}
+		let dir = relative_dir();
+		let mut files = relative(c_file(
+			dir.path(),
+			"gina_double.go",
+			"package main\n\nimport \"fmt\"\n\nfunc main() {\n\tvar n int\n\tfmt.Scan(&n)\n\tfmt.Println(n * 2)\n}\n",
+		));
+		files[0].language = "go".to_string();
+		let mut spec = spec(ECHO_SPEC);
+		spec.meta.file = "double.go".to_string();
+		spec.meta.language = "go".to_string();
+
+		let results = CompiledExecutor::go()
+			.execute_chain(&files, &spec, &spec.cases[..1], 5)
+			.await;
+		assert_eq!(results.len(), 1);
+		assert_eq!(results[0].status, TestStatus::Passed, "{:?}", results[0]);
+	}
+
+	#[tokio::test]
+	async fn test_python_verifier_uses_configured_interpreter() {
+		let dir = tempfile::tempdir().unwrap();
+		let files = c_file(
+			dir.path(),
+			"frank_double.c",
+			"#include <stdio.h>\nint main(void){printf(\"42\\n\");return 0;}\n",
+		);
+		let verifier = dir.path().join("verify.py");
+		std::fs::write(
+			&verifier,
+			"import json, sys\njson.load(sys.stdin)\nprint(json.dumps({\"pass\": True}))\n",
+		)
+		.unwrap();
+		let mut spec = spec(ECHO_SPEC);
+		spec.cases[0].check = Some(crate::models::CheckMethod::Detailed(
+			toml::from_str(&format!("python = \"{}\"", verifier.display())).unwrap(),
+		));
+
+		let result = CompiledExecutor::c()
+			.execute_case(&files, &spec, &spec.cases[0], 5)
+			.await;
+		assert_eq!(result.status, TestStatus::Passed, "{result:?}");
+
+		let missing = dir.path().join("no-such-python");
+		let result = CompiledExecutor::c()
+			.with_python_cmd(missing.to_string_lossy())
+			.execute_case(&files, &spec, &spec.cases[0], 5)
+			.await;
+		assert_ne!(result.status, TestStatus::Passed);
+		assert!(
+			result.failure.unwrap().message.contains("Failed to spawn"),
+			"verifier should run under the configured interpreter"
+		);
+	}
+}
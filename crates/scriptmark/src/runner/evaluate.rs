@@ -0,0 +1,451 @@
+use std::path::Path;
+use std::process::Output;
+use std::time::Instant;
+
+use crate::checker::{CheckInput, resolve_checker};
+use crate::models::{CaseResult, CheckMethod, ExpectFile, FailureDetail, TestCase, TestStatus};
+use crate::runner::process::{SpawnError, case_relative_path};
+
+/// Parse the JSON envelope printed by a function-call helper and check it
+/// against the case's expectations.
+///
+/// Envelope: `{"ok": true, "value": ..., "type": "..."}` or
+/// `{"ok": false, "error_type": "...", "error_message": "..."}`, optionally
+/// with a `traceback` that becomes the failure details, and with the `stdout`
+/// printed during the call. Every language's helper prints this shape. The
+/// printed output is checked against `expected_stdout` and passed to the
+/// value checker as `context.stdout`. `python_cmd` runs Python verifiers.
+pub(crate) fn evaluate_function_result(
+	stdout: &str,
+	case: &TestCase,
+	elapsed_ms: u64,
+	python_cmd: &str,
+) -> CaseResult {
+	let parsed: Result<serde_json::Value, _> = serde_json::from_str(stdout);
+
+	let json = match parsed {
+		Ok(v) => v,
+		Err(e) => {
+			return CaseResult {
+				case_name: case.name.clone(),
+				status: TestStatus::Error,
+				actual: Some(stdout.to_string()),
+				expected: None,
+				failure: Some(FailureDetail {
+					message: format!("Failed to parse helper output: {e}"),
+					details: stdout.to_string(),
+				}),
+				elapsed_ms: Some(elapsed_ms),
+				matched_name: None,
+			};
+		}
+	};
+
+	let mut result = evaluate_envelope(&json, case, elapsed_ms, python_cmd);
+	result.matched_name = matched_name(&json);
+	result
+}
+
+/// The name a helper substituted by fuzzy lookup, if it did.
+pub(crate) fn matched_name(envelope: &serde_json::Value) -> Option<String> {
+	envelope
+		.get("matched_name")
+		.and_then(|v| v.as_str())
+		.map(String::from)
+}
+
+fn evaluate_envelope(
+	json: &serde_json::Value,
+	case: &TestCase,
+	elapsed_ms: u64,
+	python_cmd: &str,
+) -> CaseResult {
+	let ok = json.get("ok").and_then(|v| v.as_bool()).unwrap_or(false);
+
+	if !ok {
+		let error_type = json
+			.get("error_type")
+			.and_then(|v| v.as_str())
+			.unwrap_or("Unknown");
+		let error_message = json
+			.get("error_message")
+			.and_then(|v| v.as_str())
+			.unwrap_or("");
+
+		// Check if this was an expected error
+		if let Some(expected_error) = &case.expect_error
+			&& error_type == expected_error
+		{
+			return CaseResult {
+				case_name: case.name.clone(),
+				status: TestStatus::Passed,
+				actual: Some(format!("{error_type}: {error_message}")),
+				expected: Some(format!("{expected_error} (expected)")),
+				failure: None,
+				elapsed_ms: Some(elapsed_ms),
+				matched_name: None,
+			};
+		}
+
+		return CaseResult {
+			case_name: case.name.clone(),
+			status: TestStatus::Failed,
+			actual: Some(format!("{error_type}: {error_message}")),
+			expected: case
+				.expect
+				.as_ref()
+				.map(|v| v.to_string())
+				.or_else(|| case.expect_error.as_ref().map(|e| format!("{e} error"))),
+			failure: Some(FailureDetail {
+				message: format!("{error_type}: {error_message}"),
+				details: json
+					.get("traceback")
+					.and_then(|v| v.as_str())
+					.unwrap_or("")
+					.trim_end()
+					.to_string(),
+			}),
+			elapsed_ms: Some(elapsed_ms),
+			matched_name: None,
+		};
+	}
+
+	// Student code returned successfully — but we expected an error?
+	if case.expect_error.is_some() {
+		let actual_value = json
+			.get("value")
+			.cloned()
+			.unwrap_or(serde_json::Value::Null);
+		return CaseResult {
+			case_name: case.name.clone(),
+			status: TestStatus::Failed,
+			actual: Some(actual_value.to_string()),
+			expected: case.expect_error.as_ref().map(|e| format!("{e} error")),
+			failure: Some(FailureDetail {
+				message: format!(
+					"Expected {} but function returned {}",
+					case.expect_error.as_deref().unwrap_or("error"),
+					actual_value
+				),
+				details: String::new(),
+			}),
+			elapsed_ms: Some(elapsed_ms),
+			matched_name: None,
+		};
+	}
+
+	if let Some(mismatch) = type_mismatch(json, case, elapsed_ms) {
+		return mismatch;
+	}
+	if let Some(mismatch) = stdout_mismatch(json, case, elapsed_ms, python_cmd) {
+		return mismatch;
+	}
+
+	// Normal return — check the value
+	let actual_value = json
+		.get("value")
+		.cloned()
+		.unwrap_or(serde_json::Value::Null);
+	let checker = resolve_checker(case.check.as_ref(), python_cmd);
+	let check_result = checker.check(&CheckInput {
+		result: actual_value.clone(),
+		expected: case.expect.clone().unwrap_or(serde_json::Value::Null),
+		context: serde_json::json!({ "stdout": printed_stdout(json) }),
+	});
+
+	if check_result.pass {
+		CaseResult {
+			case_name: case.name.clone(),
+			status: TestStatus::Passed,
+			actual: Some(actual_value.to_string()),
+			expected: case.expect.as_ref().map(|v| v.to_string()),
+			failure: None,
+			elapsed_ms: Some(elapsed_ms),
+			matched_name: None,
+		}
+	} else {
+		CaseResult {
+			case_name: case.name.clone(),
+			status: TestStatus::Failed,
+			actual: Some(actual_value.to_string()),
+			expected: case.expect.as_ref().map(|v| v.to_string()),
+			failure: Some(FailureDetail {
+				message: check_result.message,
+				details: String::new(),
+			}),
+			elapsed_ms: Some(elapsed_ms),
+			matched_name: None,
+		}
+	}
+}
+
+/// Failed result when a successful envelope's `type` differs from the case's
+/// `expect_type`. `"None"` is accepted for Python's `NoneType`.
+pub(crate) fn type_mismatch(
+	envelope: &serde_json::Value,
+	case: &TestCase,
+	elapsed_ms: u64,
+) -> Option<CaseResult> {
+	let expected = case.expect_type.as_deref()?;
+	let actual = envelope
+		.get("type")
+		.and_then(|v| v.as_str())
+		.unwrap_or("unknown");
+	if actual == expected || (expected == "None" && actual == "NoneType") {
+		return None;
+	}
+	let value = envelope
+		.get("value")
+		.cloned()
+		.unwrap_or(serde_json::Value::Null);
+	Some(CaseResult {
+		case_name: case.name.clone(),
+		status: TestStatus::Failed,
+		actual: Some(format!("{value} ({actual})")),
+		expected: Some(match &case.expect {
+			Some(v) => format!("{v} ({expected})"),
+			None => expected.to_string(),
+		}),
+		failure: Some(FailureDetail {
+			message: format!("expected a value of type {expected}, got {actual} {value}"),
+			details: String::new(),
+		}),
+		elapsed_ms: Some(elapsed_ms),
+		matched_name: None,
+	})
+}
+
+/// Failed result when what a function printed differs from the case's
+/// `expected_stdout` (byte-exact).
+pub(crate) fn stdout_mismatch(
+	envelope: &serde_json::Value,
+	case: &TestCase,
+	elapsed_ms: u64,
+	python_cmd: &str,
+) -> Option<CaseResult> {
+	let expected = case.expected_stdout.as_deref()?;
+	let printed = printed_stdout(envelope);
+	let failure = check_stream(
+		"stdout",
+		printed,
+		Some(expected),
+		None,
+		&serde_json::Value::Null,
+		python_cmd,
+	)?;
+	Some(CaseResult {
+		case_name: case.name.clone(),
+		status: TestStatus::Failed,
+		actual: Some(printed.to_string()),
+		expected: Some(expected.to_string()),
+		failure: Some(failure),
+		elapsed_ms: Some(elapsed_ms),
+		matched_name: None,
+	})
+}
+
+/// What the function printed during the call, from a helper envelope.
+fn printed_stdout(envelope: &serde_json::Value) -> &str {
+	envelope
+		.get("stdout")
+		.and_then(|v| v.as_str())
+		.unwrap_or("")
+}
+
+/// Evaluate the outcome of an IO-based test case (stdin → stdout).
+///
+/// Stdout is the checker's `result` and `expected_stdout` its `expected`;
+/// without a `check` that is a byte-exact comparison. The context carries
+/// `stdin`, `stderr` and `exit_code` for custom verifiers. `expect_exit_code`
+/// and `expected_stderr`/`stderr_check` are checked afterwards when set.
+pub(crate) fn evaluate_io_output(
+	result: Result<Output, SpawnError>,
+	case: &TestCase,
+	timeout_secs: u64,
+	start: Instant,
+	python_cmd: &str,
+) -> CaseResult {
+	let elapsed = start.elapsed().as_millis() as u64;
+
+	match result {
+		Err(SpawnError::Timeout) => CaseResult {
+			case_name: case.name.clone(),
+			status: TestStatus::Timeout,
+			actual: None,
+			expected: case.expected_stdout.clone(),
+			failure: Some(FailureDetail {
+				message: format!("Timed out after {timeout_secs}s"),
+				details: String::new(),
+			}),
+			elapsed_ms: Some(elapsed),
+			matched_name: None,
+		},
+		Err(SpawnError::Spawn(e)) => CaseResult {
+			case_name: case.name.clone(),
+			status: TestStatus::Error,
+			actual: None,
+			expected: None,
+			failure: Some(FailureDetail {
+				message: format!("Failed to spawn: {e}"),
+				details: String::new(),
+			}),
+			elapsed_ms: Some(elapsed),
+			matched_name: None,
+		},
+		Ok(output) => {
+			let actual_stdout = String::from_utf8_lossy(&output.stdout).to_string();
+			let actual_stderr = String::from_utf8_lossy(&output.stderr).to_string();
+			let exit_code = output.status.code();
+			let context = serde_json::json!({
+				"stdin": case.stdin.as_deref().unwrap_or(""),
+				"stderr": actual_stderr,
+				"exit_code": exit_code,
+			});
+			let expected = match (&case.expected_stdout, &case.check) {
+				(Some(expected), _) => Some(expected.clone()),
+				// No expectation and no checker: the program should print nothing
+				(None, None) => Some(String::new()),
+				(None, Some(_)) => None,
+			};
+
+			let failure = check_stream(
+				"stdout",
+				&actual_stdout,
+				expected.as_deref(),
+				case.check.as_ref(),
+				&context,
+				python_cmd,
+			)
+			.or_else(|| {
+				let want = case.expect_exit_code?;
+				if exit_code == Some(want) {
+					return None;
+				}
+				let got = exit_code.map_or("killed by a signal".to_string(), |c| c.to_string());
+				Some(FailureDetail {
+					message: format!("expected exit code {want}, got {got}"),
+					details: actual_stderr.clone(),
+				})
+			})
+			.or_else(|| {
+				if case.expected_stderr.is_none() && case.stderr_check.is_none() {
+					return None;
+				}
+				check_stream(
+					"stderr",
+					&actual_stderr,
+					case.expected_stderr.as_deref(),
+					case.stderr_check.as_ref(),
+					&context,
+					python_cmd,
+				)
+			});
+
+			CaseResult {
+				case_name: case.name.clone(),
+				status: if failure.is_none() {
+					TestStatus::Passed
+				} else {
+					TestStatus::Failed
+				},
+				actual: Some(actual_stdout),
+				expected,
+				failure,
+				elapsed_ms: Some(elapsed),
+				matched_name: None,
+			}
+		}
+	}
+}
+
+/// Check one output stream of an IO case; `None` when it passes.
+fn check_stream(
+	stream: &str,
+	actual: &str,
+	expected: Option<&str>,
+	check: Option<&CheckMethod>,
+	context: &serde_json::Value,
+	python_cmd: &str,
+) -> Option<FailureDetail> {
+	let checker = resolve_checker(check, python_cmd);
+	let check_result = checker.check(&CheckInput {
+		result: serde_json::Value::String(actual.to_string()),
+		expected: expected.map_or(serde_json::Value::Null, |e| {
+			serde_json::Value::String(e.to_string())
+		}),
+		context: context.clone(),
+	});
+	if check_result.pass {
+		return None;
+	}
+
+	let message = match (check, stream) {
+		(None, _) => format!("{stream} mismatch"),
+		(Some(_), "stdout") => check_result.message,
+		(Some(_), _) => format!("{stream}: {}", check_result.message),
+	};
+	Some(FailureDetail {
+		message,
+		details: format!("expected:\n{}\nactual:\n{actual}", expected.unwrap_or("")),
+	})
+}
+
+/// Check the files a passing case was expected to write into `dir`.
+///
+/// Files are checked in path order and the first failure fails the case; a
+/// missing file gets its own message rather than a content mismatch. A name
+/// outside the working dir is an error, like it is for `files`.
+pub(crate) fn check_expected_files(
+	mut result: CaseResult,
+	case: &TestCase,
+	dir: &Path,
+	python_cmd: &str,
+) -> CaseResult {
+	if result.status != TestStatus::Passed || case.expect_files.is_empty() {
+		return result;
+	}
+
+	let mut names: Vec<&String> = case.expect_files.keys().collect();
+	names.sort();
+	for name in names {
+		let (expected, check) = match &case.expect_files[name] {
+			ExpectFile::Content(content) => (Some(content.as_str()), None),
+			ExpectFile::Checked { expect, check } => (expect.as_deref(), check.as_deref()),
+		};
+
+		let Some(rel) = case_relative_path(name) else {
+			result.status = TestStatus::Error;
+			result.failure = Some(FailureDetail {
+				message: format!(
+					"Expected file '{name}' must be a relative path inside the working dir"
+				),
+				details: String::new(),
+			});
+			break;
+		};
+		let failure = match std::fs::read(dir.join(rel)) {
+			Ok(bytes) => check_stream(
+				&format!("file '{name}'"),
+				&String::from_utf8_lossy(&bytes),
+				expected,
+				check,
+				&serde_json::json!({ "path": name }),
+				python_cmd,
+			),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Some(FailureDetail {
+				message: format!("expected file '{name}' was not created"),
+				details: String::new(),
+			}),
+			Err(e) => Some(FailureDetail {
+				message: format!("could not read file '{name}': {e}"),
+				details: String::new(),
+			}),
+		};
+		if let Some(failure) = failure {
+			result.status = TestStatus::Failed;
+			result.failure = Some(failure);
+			break;
+		}
+	}
+	result
+}
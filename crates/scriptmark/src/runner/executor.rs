@@ -1,21 +1,202 @@
+use std::collections::HashMap;
 use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 
-use crate::models::{CaseResult, StudentFile, TestCase, TestSpec};
+use crate::models::{CaseResult, FailureDetail, StudentFile, TestCase, TestSpec, TestStatus};
+
+/// Boxed future returned by [`Executor`] methods (keeps the trait object-safe).
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 /// Language-specific execution logic.
 ///
 /// Each language has one implementation that knows how to invoke student code
-/// and collect results.
+/// and collect results. Executors receive all of a student's files for their
+/// language and pick the one matching `spec.meta.file` themselves.
 pub trait Executor: Send + Sync {
 	/// Language identifier (e.g. "python", "cpp").
 	fn language(&self) -> &str;
 
-	/// Execute a single test case for a single student file.
-	fn execute_case(
-		&self,
-		student_file: &StudentFile,
-		spec: &TestSpec,
-		case: &TestCase,
+	/// Python interpreter for teacher-side scripts run alongside this
+	/// executor's cases (verifier checks, Python oracles).
+	fn python_cmd(&self) -> &str;
+
+	/// Execute a single test case against a student's submission.
+	fn execute_case<'a>(
+		&'a self,
+		student_files: &'a [StudentFile],
+		spec: &'a TestSpec,
+		case: &'a TestCase,
+		timeout_secs: u64,
+	) -> BoxFuture<'a, CaseResult>;
+
+	/// Execute all cases of a spec in one go (chain mode).
+	///
+	/// Only used when the spec needs shared state between cases (teacher
+	/// imports, per-case functions or class cases). The default runs cases
+	/// one by one and rejects class cases.
+	fn execute_chain<'a>(
+		&'a self,
+		student_files: &'a [StudentFile],
+		spec: &'a TestSpec,
+		cases: &'a [TestCase],
 		timeout_secs: u64,
-	) -> impl Future<Output = CaseResult> + Send;
+	) -> BoxFuture<'a, Vec<CaseResult>> {
+		Box::pin(async move {
+			let mut results = Vec::with_capacity(cases.len());
+			for case in cases {
+				if case.class.is_some() {
+					results.push(CaseResult {
+						case_name: case.name.clone(),
+						status: TestStatus::Error,
+						actual: None,
+						expected: None,
+						failure: Some(FailureDetail {
+							message: format!(
+								"Class cases are not supported for {}",
+								self.language()
+							),
+							details: String::new(),
+						}),
+						elapsed_ms: Some(0),
+						matched_name: None,
+					});
+					continue;
+				}
+				let case_timeout = case.timeout.unwrap_or(timeout_secs);
+				results.push(
+					self.execute_case(student_files, spec, case, case_timeout)
+						.await,
+				);
+			}
+			results
+		})
+	}
+}
+
+/// Executors keyed by language identifier.
+///
+/// The orchestrator looks up `TestMeta.language` here and hands the executor
+/// the student files whose `StudentFile.language` matches. Cloning is cheap.
+#[derive(Clone, Default)]
+pub struct ExecutorRegistry {
+	executors: HashMap<String, Arc<dyn Executor>>,
+}
+
+impl ExecutorRegistry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registry with every built-in executor.
+	pub fn builtin(python_cmd: &str) -> Self {
+		use crate::runner::compiled::CompiledExecutor;
+		use crate::runner::java::JavaExecutor;
+		use crate::runner::node::NodeExecutor;
+		use crate::runner::python::PythonExecutor;
+
+		Self::new()
+			.with_executor(PythonExecutor::with_python_cmd(python_cmd))
+			.with_executor(CompiledExecutor::c().with_python_cmd(python_cmd))
+			.with_executor(CompiledExecutor::cpp().with_python_cmd(python_cmd))
+			.with_executor(CompiledExecutor::rust().with_python_cmd(python_cmd))
+			.with_executor(CompiledExecutor::go().with_python_cmd(python_cmd))
+			.with_executor(JavaExecutor::new().with_python_cmd(python_cmd))
+			.with_executor(NodeExecutor::javascript().with_python_cmd(python_cmd))
+			.with_executor(NodeExecutor::typescript().with_python_cmd(python_cmd))
+	}
+
+	/// Register an executor under its own `language()`, replacing any previous one.
+	pub fn with_executor(mut self, executor: impl Executor + 'static) -> Self {
+		self.register(Arc::new(executor));
+		self
+	}
+
+	/// Register an executor under its own `language()`, replacing any previous one.
+	pub fn register(&mut self, executor: Arc<dyn Executor>) {
+		self.executors
+			.insert(executor.language().to_string(), executor);
+	}
+
+	pub fn get(&self, language: &str) -> Option<&Arc<dyn Executor>> {
+		self.executors.get(language)
+	}
+
+	pub fn languages(&self) -> Vec<&str> {
+		let mut langs: Vec<&str> = self.executors.keys().map(|k| k.as_str()).collect();
+		langs.sort();
+		langs
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::TestStatus;
+
+	struct EchoExecutor(&'static str);
+
+	impl Executor for EchoExecutor {
+		fn language(&self) -> &str {
+			self.0
+		}
+
+		fn python_cmd(&self) -> &str {
+			"python3"
+		}
+
+		fn execute_case<'a>(
+			&'a self,
+			_student_files: &'a [StudentFile],
+			_spec: &'a TestSpec,
+			case: &'a TestCase,
+			_timeout_secs: u64,
+		) -> BoxFuture<'a, CaseResult> {
+			Box::pin(async move {
+				CaseResult {
+					case_name: case.name.clone(),
+					status: TestStatus::Passed,
+					actual: Some(self.0.to_string()),
+					expected: None,
+					failure: None,
+					elapsed_ms: Some(0),
+					matched_name: None,
+				}
+			})
+		}
+	}
+
+	#[test]
+	fn test_registry_lookup_by_language() {
+		let registry = ExecutorRegistry::new()
+			.with_executor(EchoExecutor("python"))
+			.with_executor(EchoExecutor("cpp"));
+		assert_eq!(registry.languages(), vec!["cpp", "python"]);
+		assert_eq!(registry.get("cpp").unwrap().language(), "cpp");
+		assert!(registry.get("java").is_none());
+	}
+
+	#[tokio::test]
+	async fn test_default_chain_runs_each_case() {
+		let spec: TestSpec = toml::from_str(
+			r#"
+[meta]
+name = "echo"
+file = "main.cpp"
+language = "cpp"
+
+[[cases]]
+name = "a"
+
+[[cases]]
+name = "b"
+"#,
+		)
+		.unwrap();
+		let executor = EchoExecutor("cpp");
+		let results = executor.execute_chain(&[], &spec, &spec.cases, 5).await;
+		assert_eq!(results.len(), 2);
+		assert_eq!(results[1].case_name, "b");
+		assert_eq!(results[0].actual.as_deref(), Some("cpp"));
+	}
 }
@@ -1,35 +1,37 @@
 use crate::models::TestCase;
+use crate::models::spec::Parametrize;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
 
-use crate::runner::generator::generate_value;
+use crate::runner::generator::{Bindings, Expr, GeneratorError, parse};
 
 /// Expand parametrized TestCases into concrete TestCases.
 /// Non-parametrized cases pass through unchanged.
-pub fn expand_cases(cases: &[TestCase]) -> Vec<TestCase> {
+///
+/// Arguments are generated in [`arg_order`], so a generator can use the
+/// arguments before it. Any generator error fails the whole expansion.
+pub fn expand_cases(cases: &[TestCase]) -> Result<Vec<TestCase>, GeneratorError> {
 	let mut result = Vec::new();
 
 	for case in cases {
 		if let Some(param) = &case.parametrize {
 			let seed = param.seed.unwrap_or(0);
 			let mut rng = StdRng::seed_from_u64(seed);
-
-			// Sort arg names for deterministic positional ordering
-			let mut arg_names: Vec<&String> = param.args.keys().collect();
-			arg_names.sort();
+			let generators = generators(param)?;
 
 			for i in 0..param.count {
-				let mut args = Vec::new();
-				for name in &arg_names {
-					let expr = &param.args[*name];
-					match generate_value(expr, &mut rng) {
-						Ok(val) => args.push(val),
-						Err(_) => args.push(serde_json::Value::Null),
-					}
+				let mut bindings = Bindings::new();
+				let mut args = Vec::with_capacity(generators.len());
+				for (name, expr) in &generators {
+					let value = expr
+						.generate(&bindings, &mut rng)
+						.map_err(|e| argument_error(name, e))?;
+					bindings.insert(name.clone(), value.clone());
+					args.push(value);
 				}
 
 				result.push(TestCase {
-					name: format!("{} [{}]", case.name, i),
+					name: case_name(&case.name, i),
 					args,
 					check: case.check.clone(),
 					timeout: case.timeout,
@@ -41,7 +43,71 @@ pub fn expand_cases(cases: &[TestCase]) -> Vec<TestCase> {
 		}
 	}
 
-	result
+	Ok(result)
+}
+
+/// Name of the `i`th case generated from a parametrized case.
+pub fn case_name(name: &str, i: usize) -> String {
+	format!("{name} [{i}]")
+}
+
+/// Positional order of a parametrized case's arguments: `order` when given
+/// (it must list every argument once), otherwise alphabetical.
+pub fn arg_order(param: &Parametrize) -> Result<Vec<String>, GeneratorError> {
+	if param.order.is_empty() {
+		let mut names: Vec<String> = param.args.keys().cloned().collect();
+		names.sort();
+		return Ok(names);
+	}
+	for (i, name) in param.order.iter().enumerate() {
+		if !param.args.contains_key(name) {
+			return Err(GeneratorError::Order(format!(
+				"'{name}' has no generator in args"
+			)));
+		}
+		if param.order[..i].contains(name) {
+			return Err(GeneratorError::Order(format!("'{name}' is listed twice")));
+		}
+	}
+	if let Some(name) = param.args.keys().find(|k| !param.order.contains(k)) {
+		return Err(GeneratorError::Order(format!("'{name}' is missing")));
+	}
+	Ok(param.order.clone())
+}
+
+/// Parsed generators in argument order, each checked to refer only to
+/// arguments generated before it.
+fn generators(param: &Parametrize) -> Result<Vec<(String, Expr)>, GeneratorError> {
+	let order = arg_order(param)?;
+	let mut generators: Vec<(String, Expr)> = Vec::with_capacity(order.len());
+	for name in order {
+		let expr = parse(&param.args[&name]).map_err(|e| argument_error(&name, e))?;
+		for target in expr.refs() {
+			if generators.iter().any(|(earlier, _)| earlier == target) {
+				continue;
+			}
+			let reason = if target == name {
+				"itself".to_string()
+			} else if param.args.contains_key(target) {
+				format!("'{target}', which is generated after it; list it earlier in `order`")
+			} else {
+				format!("unknown argument '{target}'")
+			};
+			return Err(argument_error(
+				&name,
+				GeneratorError::InvalidExpression(format!("refers to {reason}")),
+			));
+		}
+		generators.push((name, expr));
+	}
+	Ok(generators)
+}
+
+fn argument_error(name: &str, source: GeneratorError) -> GeneratorError {
+	GeneratorError::Argument {
+		arg: name.to_string(),
+		source: Box::new(source),
+	}
 }
 
 #[cfg(test)]
@@ -58,7 +124,7 @@ mod tests {
 			expect: Some(serde_json::json!(5)),
 			..Default::default()
 		}];
-		let expanded = expand_cases(&cases);
+		let expanded = expand_cases(&cases).unwrap();
 		assert_eq!(expanded.len(), 1);
 		assert_eq!(expanded[0].name, "simple");
 	}
@@ -75,11 +141,12 @@ mod tests {
 				count: 5,
 				seed: Some(42),
 				args,
+				order: vec![],
 				oracle: Oracle::default(),
 			}),
 			..Default::default()
 		}];
-		let expanded = expand_cases(&cases);
+		let expanded = expand_cases(&cases).unwrap();
 		assert_eq!(expanded.len(), 5);
 		for (i, case) in expanded.iter().enumerate() {
 			assert_eq!(case.name, format!("random test [{}]", i));
@@ -99,13 +166,74 @@ mod tests {
 				count: 3,
 				seed: Some(99),
 				args,
+				order: vec![],
 				oracle: Oracle::default(),
 			}),
 			..Default::default()
 		}];
-		let run1 = expand_cases(&cases);
-		let run2 = expand_cases(&cases);
+		let run1 = expand_cases(&cases).unwrap();
+		let run2 = expand_cases(&cases).unwrap();
 		assert_eq!(run1[0].args, run2[0].args);
 		assert_eq!(run1[1].args, run2[1].args);
 	}
+
+	fn parametrized(order: &[&str], args: &[(&str, &str)]) -> Vec<TestCase> {
+		vec![TestCase {
+			name: "dependent".into(),
+			parametrize: Some(Parametrize {
+				count: 20,
+				seed: Some(5),
+				args: args
+					.iter()
+					.map(|(k, v)| (k.to_string(), v.to_string()))
+					.collect(),
+				order: order.iter().map(|s| s.to_string()).collect(),
+				oracle: Oracle::default(),
+			}),
+			..Default::default()
+		}]
+	}
+
+	#[test]
+	fn test_dependent_args_follow_order() {
+		let cases = parametrized(
+			&["nums", "k", "target"],
+			&[
+				("nums", "list(int(0, 50), 1, 6)"),
+				("k", "int(0, len(nums) - 1)"),
+				("target", "choice($nums)"),
+			],
+		);
+		for case in expand_cases(&cases).unwrap() {
+			let nums = case.args[0].as_array().unwrap();
+			let k = case.args[1].as_u64().unwrap() as usize;
+			assert!(k < nums.len(), "{:?}", case.args);
+			assert!(nums.contains(&case.args[2]), "{:?}", case.args);
+		}
+	}
+
+	#[test]
+	fn test_order_errors() {
+		let args = [
+			("nums", "list(int(0, 5), 1, 3)"),
+			("k", "int(0, len(nums))"),
+		];
+		// Alphabetical order generates k before nums
+		let err = expand_cases(&parametrized(&[], &args)).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"argument 'k': invalid generator expression: refers to 'nums', which is generated after it; list it earlier in `order`"
+		);
+
+		let err = expand_cases(&parametrized(&["nums"], &args)).unwrap_err();
+		assert_eq!(err.to_string(), "invalid argument order: 'k' is missing");
+		let err = expand_cases(&parametrized(&["nums", "k", "nums"], &args)).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"invalid argument order: 'nums' is listed twice"
+		);
+
+		let err = expand_cases(&parametrized(&[], &[("a", "choice($b)"), ("c", "a")])).unwrap_err();
+		assert!(err.to_string().contains("unknown argument 'b'"), "{err}");
+	}
 }
@@ -1,7 +1,15 @@
+use std::cmp::Ordering;
+
 use rand::Rng;
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::{Distribution, Uniform};
 use rand::rngs::StdRng;
+use regex_syntax::hir::{Class, ClassUnicode, ClassUnicodeRange, Hir, HirKind};
 use serde_json::Value;
 
+/// Extra repetitions allowed for an unbounded regex repeat (`*`, `+`, `{n,}`).
+const UNBOUNDED_REPEAT: u32 = 8;
+
 /// Parse a generator expression and produce a random value.
 ///
 /// Supported expressions:
@@ -9,59 +17,24 @@ use serde_json::Value;
 /// - `float(min, max)` — random float in [min, max]
 /// - `bool()` — random boolean
 /// - `str(min_len, max_len)` — random alphanumeric string
-/// - `choice([v1, v2, ...])` — random pick from JSON array
+/// - `str(min_len, max_len, "charset")` — random string over the given characters
+/// - `str("regex")` — random string matching the pattern
+/// - `choice([v1, v2, ...])` — random pick; `choice([...], [w1, w2, ...])` weights the pick
 /// - `list(gen_expr, min_len, max_len)` — list of random values
+/// - `unique_list(gen_expr, min_len, max_len)` — list without repeated values
+/// - `sorted_list(gen_expr, min_len, max_len)` — list in ascending order
+/// - `tuple(gen_expr, ...)` — one value per generator, as a `$tuple`
+/// - `dict(key_expr, value_expr, min_len, max_len)` — object with distinct keys
+/// - `matrix(gen_expr, rows, cols)` — list of `rows` lists of `cols` values
+///
+/// - `len(x)` — length of a list, object or string
+///
+/// Arguments are expressions themselves, so `list(int(0, 9), int(1, 3), 5)`
+/// draws the length too. JSON literals (numbers, strings, `true`, `false`,
+/// `null`, arrays, objects) stand for themselves, `+`, `-` and `*` combine
+/// numbers, and `$name` (or a bare `name`) is another argument's value.
 pub fn generate_value(expr: &str, rng: &mut StdRng) -> Result<Value, GeneratorError> {
-	let expr = expr.trim();
-
-	if let Some(inner) = strip_call(expr, "int") {
-		let (min, max) = parse_two_nums::<i64>(&inner)?;
-		let val = rng.random_range(min..=max);
-		return Ok(Value::from(val));
-	}
-	if let Some(inner) = strip_call(expr, "float") {
-		let (min, max) = parse_two_nums::<f64>(&inner)?;
-		let val: f64 = rng.random_range(min..=max);
-		return Ok(Value::from(val));
-	}
-	if strip_call(expr, "bool").is_some() {
-		return Ok(Value::from(rng.random_bool(0.5)));
-	}
-	if let Some(inner) = strip_call(expr, "str") {
-		let (min_len, max_len) = parse_two_nums::<usize>(&inner)?;
-		let len = rng.random_range(min_len..=max_len);
-		let s: String = (0..len)
-			.map(|_| {
-				let idx = rng.random_range(0u8..36);
-				if idx < 10 {
-					(b'0' + idx) as char
-				} else {
-					(b'a' + idx - 10) as char
-				}
-			})
-			.collect();
-		return Ok(Value::from(s));
-	}
-	if let Some(inner) = strip_call(expr, "choice") {
-		let arr: Vec<Value> = serde_json::from_str(&inner)
-			.map_err(|e| GeneratorError::ParseError(format!("choice array: {e}")))?;
-		if arr.is_empty() {
-			return Err(GeneratorError::InvalidExpression(
-				"choice with empty array".into(),
-			));
-		}
-		let idx = rng.random_range(0..arr.len());
-		return Ok(arr[idx].clone());
-	}
-	if let Some(inner) = strip_call(expr, "list") {
-		let (gen_expr, min_len, max_len) = parse_list_args(&inner)?;
-		let len = rng.random_range(min_len..=max_len);
-		let items: Result<Vec<Value>, _> =
-			(0..len).map(|_| generate_value(&gen_expr, rng)).collect();
-		return Ok(Value::from(items?));
-	}
-
-	Err(GeneratorError::InvalidExpression(expr.to_string()))
+	parse(expr)?.generate(&Bindings::new(), rng)
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -70,70 +43,686 @@ pub enum GeneratorError {
 	InvalidExpression(String),
 	#[error("parse error in generator: {0}")]
 	ParseError(String),
+	#[error("argument '{arg}': {source}")]
+	Argument {
+		arg: String,
+		#[source]
+		source: Box<GeneratorError>,
+	},
+	#[error("invalid argument order: {0}")]
+	Order(String),
+}
+
+/// A parsed generator expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+	/// A number, string, `true`, `false` or `null`.
+	Literal(Value),
+	/// `[a, b, ...]`; each element is generated.
+	Array(Vec<Expr>),
+	/// `{"key": a, ...}`; each value is generated.
+	Object(Vec<(String, Expr)>),
+	/// `name(a, b, ...)`
+	Call(String, Vec<Expr>),
+	/// `$name`, or a bare `name`: the value of another argument.
+	Ref(String),
+	/// `a + b`, `a - b` or `a * b`.
+	BinOp(char, Box<Expr>, Box<Expr>),
+}
+
+/// Parse a generator expression without evaluating it.
+pub fn parse(expr: &str) -> Result<Expr, GeneratorError> {
+	let mut parser = Parser { src: expr, pos: 0 };
+	let parsed = parser.expr()?;
+	parser.skip_ws();
+	match parser.peek() {
+		None => Ok(parsed),
+		Some(c) => Err(parser.error(&format!("unexpected '{c}'"))),
+	}
+}
+
+/// Recursive-descent parser over the expression source.
+struct Parser<'a> {
+	src: &'a str,
+	pos: usize,
+}
+
+impl<'a> Parser<'a> {
+	fn peek(&self) -> Option<char> {
+		self.src[self.pos..].chars().next()
+	}
+
+	fn bump(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.pos += c.len_utf8();
+		Some(c)
+	}
+
+	fn skip_ws(&mut self) {
+		while self.peek().is_some_and(char::is_whitespace) {
+			self.bump();
+		}
+	}
+
+	fn error(&self, message: &str) -> GeneratorError {
+		GeneratorError::ParseError(format!(
+			"{message} at column {} of `{}`",
+			self.pos + 1,
+			self.src
+		))
+	}
+
+	fn expect(&mut self, want: char) -> Result<(), GeneratorError> {
+		self.skip_ws();
+		match self.peek() {
+			Some(c) if c == want => {
+				self.bump();
+				Ok(())
+			}
+			Some(c) => Err(self.error(&format!("expected '{want}', found '{c}'"))),
+			None => Err(self.error(&format!("expected '{want}', found end of expression"))),
+		}
+	}
+
+	/// `term (('+' | '-') term)*`
+	fn expr(&mut self) -> Result<Expr, GeneratorError> {
+		let mut lhs = self.term()?;
+		loop {
+			self.skip_ws();
+			match self.peek() {
+				Some(op @ ('+' | '-')) => {
+					self.bump();
+					lhs = Expr::BinOp(op, Box::new(lhs), Box::new(self.term()?));
+				}
+				_ => return Ok(lhs),
+			}
+		}
+	}
+
+	/// `atom ('*' atom)*`
+	fn term(&mut self) -> Result<Expr, GeneratorError> {
+		let mut lhs = self.atom()?;
+		loop {
+			self.skip_ws();
+			if self.peek() != Some('*') {
+				return Ok(lhs);
+			}
+			self.bump();
+			lhs = Expr::BinOp('*', Box::new(lhs), Box::new(self.atom()?));
+		}
+	}
+
+	fn atom(&mut self) -> Result<Expr, GeneratorError> {
+		self.skip_ws();
+		match self.peek() {
+			None => Err(self.error("unexpected end of expression")),
+			Some('$') => {
+				self.bump();
+				let name = self.ident();
+				if name.is_empty() {
+					return Err(self.error("expected an argument name after '$'"));
+				}
+				Ok(Expr::Ref(name.to_string()))
+			}
+			Some('[') => {
+				self.bump();
+				Ok(Expr::Array(self.list(']', Self::expr)?))
+			}
+			Some('{') => {
+				self.bump();
+				let entries = self.list('}', |p| {
+					p.skip_ws();
+					let key = match p.peek() {
+						Some(q @ ('"' | '\'')) => p.string(q)?,
+						_ => return Err(p.error("expected a string key")),
+					};
+					p.expect(':')?;
+					Ok((key, p.expr()?))
+				})?;
+				Ok(Expr::Object(entries))
+			}
+			Some(q @ ('"' | '\'')) => Ok(Expr::Literal(Value::from(self.string(q)?))),
+			Some(c) if c == '-' || c == '+' || c == '.' || c.is_ascii_digit() => self.number(),
+			Some(c) if c == '_' || c.is_alphabetic() => {
+				let name = self.ident();
+				self.skip_ws();
+				if self.peek() == Some('(') {
+					self.bump();
+					return Ok(Expr::Call(name.to_string(), self.list(')', Self::expr)?));
+				}
+				match name {
+					"true" => Ok(Expr::Literal(Value::Bool(true))),
+					"false" => Ok(Expr::Literal(Value::Bool(false))),
+					"null" => Ok(Expr::Literal(Value::Null)),
+					_ => Ok(Expr::Ref(name.to_string())),
+				}
+			}
+			Some(c) => Err(self.error(&format!("unexpected '{c}'"))),
+		}
+	}
+
+	fn ident(&mut self) -> &'a str {
+		let start = self.pos;
+		while self.peek().is_some_and(|c| c == '_' || c.is_alphanumeric()) {
+			self.bump();
+		}
+		&self.src[start..self.pos]
+	}
+
+	/// Comma-separated items up to `close` (already past the opening bracket).
+	fn list<T>(
+		&mut self,
+		close: char,
+		mut item: impl FnMut(&mut Self) -> Result<T, GeneratorError>,
+	) -> Result<Vec<T>, GeneratorError> {
+		let mut items = Vec::new();
+		loop {
+			self.skip_ws();
+			if self.peek() == Some(close) {
+				self.bump();
+				return Ok(items);
+			}
+			items.push(item(self)?);
+			self.skip_ws();
+			match self.peek() {
+				Some(',') => {
+					self.bump();
+				}
+				Some(c) if c == close => {}
+				Some(c) => {
+					return Err(self.error(&format!("expected ',' or '{close}', found '{c}'")));
+				}
+				None => return Err(self.error(&format!("missing '{close}'"))),
+			}
+		}
+	}
+
+	/// A quoted string. Unknown escapes such as `\d` are kept as written, so
+	/// regex patterns need no double escaping.
+	fn string(&mut self, quote: char) -> Result<String, GeneratorError> {
+		self.bump();
+		let mut out = String::new();
+		loop {
+			match self.bump() {
+				None => return Err(self.error("unterminated string")),
+				Some(c) if c == quote => return Ok(out),
+				Some('\\') => match self.bump() {
+					Some('n') => out.push('\n'),
+					Some('t') => out.push('\t'),
+					Some('r') => out.push('\r'),
+					Some(c @ ('\\' | '"' | '\'')) => out.push(c),
+					Some(c) => {
+						out.push('\\');
+						out.push(c);
+					}
+					None => return Err(self.error("unterminated string")),
+				},
+				Some(c) => out.push(c),
+			}
+		}
+	}
+
+	fn number(&mut self) -> Result<Expr, GeneratorError> {
+		let start = self.pos;
+		if matches!(self.peek(), Some('-' | '+')) {
+			self.bump();
+		}
+		let mut is_float = false;
+		while let Some(c) = self.peek() {
+			match c {
+				'0'..='9' => {}
+				'.' | 'e' | 'E' => is_float = true,
+				'-' | '+' if matches!(self.src[..self.pos].chars().last(), Some('e' | 'E')) => {}
+				_ => break,
+			}
+			self.bump();
+		}
+		let text = &self.src[start..self.pos];
+		let value = if is_float {
+			text.parse::<f64>().ok().map(Value::from)
+		} else {
+			text.parse::<i64>().ok().map(Value::from)
+		};
+		value.map(Expr::Literal).ok_or_else(|| {
+			self.pos = start;
+			self.error(&format!("invalid number '{text}'"))
+		})
+	}
+}
+
+/// The arguments generated so far, by name.
+pub type Bindings = serde_json::Map<String, Value>;
+
+/// What an expression sees while generating.
+struct Ctx<'a> {
+	rng: &'a mut StdRng,
+	bindings: &'a Bindings,
+}
+
+impl Expr {
+	/// Produce a random value; literals produce themselves and references
+	/// produce the value bound to that argument.
+	pub fn generate(&self, bindings: &Bindings, rng: &mut StdRng) -> Result<Value, GeneratorError> {
+		self.eval(&mut Ctx { rng, bindings })
+	}
+
+	/// Names of the arguments this expression refers to.
+	pub fn refs(&self) -> Vec<&str> {
+		match self {
+			Expr::Literal(_) => Vec::new(),
+			Expr::Ref(name) => vec![name.as_str()],
+			Expr::Array(items) | Expr::Call(_, items) => {
+				items.iter().flat_map(Expr::refs).collect()
+			}
+			Expr::Object(entries) => entries.iter().flat_map(|(_, e)| e.refs()).collect(),
+			Expr::BinOp(_, lhs, rhs) => lhs.refs().into_iter().chain(rhs.refs()).collect(),
+		}
+	}
+
+	fn eval(&self, cx: &mut Ctx<'_>) -> Result<Value, GeneratorError> {
+		match self {
+			Expr::Literal(value) => Ok(value.clone()),
+			Expr::Ref(name) => cx.bindings.get(name).cloned().ok_or_else(|| {
+				GeneratorError::InvalidExpression(format!("unknown argument '{name}'"))
+			}),
+			Expr::Array(items) => items.iter().map(|e| e.eval(cx)).collect(),
+			Expr::Object(entries) => entries
+				.iter()
+				.map(|(k, e)| Ok((k.clone(), e.eval(cx)?)))
+				.collect::<Result<serde_json::Map<_, _>, _>>()
+				.map(Value::Object),
+			Expr::Call(name, args) => call(name, args, cx),
+			Expr::BinOp(op, lhs, rhs) => arithmetic(*op, lhs.eval(cx)?, rhs.eval(cx)?),
+		}
+	}
+}
+
+/// `+`, `-` or `*` on two numbers; integers stay integers unless they overflow.
+fn arithmetic(op: char, lhs: Value, rhs: Value) -> Result<Value, GeneratorError> {
+	if let (Some(a), Some(b)) = (lhs.as_i64(), rhs.as_i64()) {
+		let result = match op {
+			'+' => a.checked_add(b),
+			'-' => a.checked_sub(b),
+			_ => a.checked_mul(b),
+		};
+		if let Some(result) = result {
+			return Ok(Value::from(result));
+		}
+	}
+	match (lhs.as_f64(), rhs.as_f64()) {
+		(Some(a), Some(b)) => Ok(Value::from(match op {
+			'+' => a + b,
+			'-' => a - b,
+			_ => a * b,
+		})),
+		_ => Err(GeneratorError::InvalidExpression(format!(
+			"cannot compute {lhs} {op} {rhs}"
+		))),
+	}
+}
+
+fn call(name: &str, args: &[Expr], cx: &mut Ctx<'_>) -> Result<Value, GeneratorError> {
+	match (name, args) {
+		("int", [min, max]) => {
+			let (min, max) = (int_arg(min, cx)?, int_arg(max, cx)?);
+			check_range(name, min, max)?;
+			Ok(Value::from(cx.rng.random_range(min..=max)))
+		}
+		("float", [min, max]) => {
+			let (min, max) = (float_arg(min, cx)?, float_arg(max, cx)?);
+			check_range(name, min, max)?;
+			let uniform = Uniform::new_inclusive(min, max).map_err(|e| {
+				GeneratorError::InvalidExpression(format!(
+					"float: cannot draw from {min} to {max}: {e}"
+				))
+			})?;
+			Ok(Value::from(uniform.sample(cx.rng)))
+		}
+		("bool", []) => Ok(Value::from(cx.rng.random_bool(0.5))),
+		("len", [value]) => match value.eval(cx)? {
+			Value::Array(items) => Ok(Value::from(items.len())),
+			Value::Object(map) => Ok(Value::from(map.len())),
+			Value::String(s) => Ok(Value::from(s.chars().count())),
+			other => Err(GeneratorError::InvalidExpression(format!(
+				"len needs a list, object or string, got {other}"
+			))),
+		},
+		("str", [pattern]) => {
+			let pattern = str_arg(pattern, cx)?;
+			let hir = regex_syntax::Parser::new().parse(&pattern).map_err(|e| {
+				GeneratorError::InvalidExpression(format!("str: bad pattern '{pattern}': {e}"))
+			})?;
+			let mut out = Vec::new();
+			generate_match(&hir, cx.rng, &mut out)?;
+			let out = String::from_utf8(out).map_err(|_| {
+				GeneratorError::InvalidExpression(format!(
+					"str: pattern '{pattern}' generated invalid UTF-8"
+				))
+			})?;
+			Ok(Value::from(out))
+		}
+		("str", [min, max]) => {
+			let alphabet: Vec<char> = ('0'..='9').chain('a'..='z').collect();
+			random_string(name, &alphabet, min, max, cx)
+		}
+		("str", [min, max, charset]) => {
+			let alphabet: Vec<char> = str_arg(charset, cx)?.chars().collect();
+			if alphabet.is_empty() {
+				return Err(GeneratorError::InvalidExpression(
+					"str: empty charset".into(),
+				));
+			}
+			random_string(name, &alphabet, min, max, cx)
+		}
+		("choice", [options]) | ("choice", [options, _]) => {
+			let weights = match args.get(1) {
+				Some(weights) => Some(weights.eval(cx)?),
+				None => None,
+			};
+			choice(options, weights, cx)
+		}
+		("list", [item, min, max]) => {
+			let len = len_between(name, min, max, cx)?;
+			(0..len).map(|_| item.eval(cx)).collect()
+		}
+		("unique_list", [item, min, max]) => {
+			let len = len_between(name, min, max, cx)?;
+			Ok(Value::Array(distinct(name, item, len, cx)?))
+		}
+		("sorted_list", [item, min, max]) => {
+			let len = len_between(name, min, max, cx)?;
+			let mut items = (0..len)
+				.map(|_| item.eval(cx))
+				.collect::<Result<Vec<_>, _>>()?;
+			items.sort_by(compare_values);
+			Ok(Value::Array(items))
+		}
+		("tuple", items) => {
+			let items = items
+				.iter()
+				.map(|e| e.eval(cx))
+				.collect::<Result<Vec<_>, _>>()?;
+			Ok(serde_json::json!({ "$tuple": items }))
+		}
+		("dict", [key, value, min, max]) => {
+			let len = len_between(name, min, max, cx)?;
+			let mut map = serde_json::Map::new();
+			for key in distinct(name, key, len, cx)? {
+				let key = match key {
+					Value::String(s) => s,
+					other => other.to_string(),
+				};
+				map.insert(key, value.eval(cx)?);
+			}
+			Ok(Value::Object(map))
+		}
+		("matrix", [item, rows, cols]) => {
+			let rows = usize_arg(rows, cx)?;
+			let cols = usize_arg(cols, cx)?;
+			(0..rows)
+				.map(|_| {
+					(0..cols)
+						.map(|_| item.eval(cx))
+						.collect::<Result<Value, _>>()
+				})
+				.collect()
+		}
+		(
+			"int" | "float" | "bool" | "len" | "str" | "choice" | "list" | "unique_list"
+			| "sorted_list" | "dict" | "matrix",
+			_,
+		) => Err(GeneratorError::InvalidExpression(format!(
+			"{name} does not take {} arguments",
+			args.len()
+		))),
+		_ => Err(GeneratorError::InvalidExpression(format!(
+			"unknown generator '{name}'"
+		))),
+	}
 }
 
-/// Strip a function call: `name(inner)` → Some(inner)
-fn strip_call(expr: &str, name: &str) -> Option<String> {
-	let expr = expr.trim();
-	if expr.starts_with(name) && expr[name.len()..].starts_with('(') && expr.ends_with(')') {
-		Some(expr[name.len() + 1..expr.len() - 1].to_string())
-	} else {
-		None
+fn int_arg(expr: &Expr, cx: &mut Ctx<'_>) -> Result<i64, GeneratorError> {
+	let value = expr.eval(cx)?;
+	value.as_i64().ok_or_else(|| {
+		GeneratorError::InvalidExpression(format!("expected an integer, got {value}"))
+	})
+}
+
+fn float_arg(expr: &Expr, cx: &mut Ctx<'_>) -> Result<f64, GeneratorError> {
+	let value = expr.eval(cx)?;
+	value
+		.as_f64()
+		.ok_or_else(|| GeneratorError::InvalidExpression(format!("expected a number, got {value}")))
+}
+
+fn usize_arg(expr: &Expr, cx: &mut Ctx<'_>) -> Result<usize, GeneratorError> {
+	let value = expr.eval(cx)?;
+	value.as_u64().map(|n| n as usize).ok_or_else(|| {
+		GeneratorError::InvalidExpression(format!("expected a non-negative integer, got {value}"))
+	})
+}
+
+fn str_arg(expr: &Expr, cx: &mut Ctx<'_>) -> Result<String, GeneratorError> {
+	match expr.eval(cx)? {
+		Value::String(s) => Ok(s),
+		other => Err(GeneratorError::InvalidExpression(format!(
+			"expected a string, got {other}"
+		))),
 	}
 }
 
-fn parse_two_nums<T: std::str::FromStr>(s: &str) -> Result<(T, T), GeneratorError>
-where
-	T::Err: std::fmt::Display,
-{
-	let parts: Vec<&str> = s.split(',').collect();
-	if parts.len() != 2 {
-		return Err(GeneratorError::ParseError(format!(
-			"expected 2 args, got {}",
-			parts.len()
+fn check_range<T: PartialOrd + std::fmt::Display>(
+	name: &str,
+	min: T,
+	max: T,
+) -> Result<(), GeneratorError> {
+	if min > max {
+		return Err(GeneratorError::InvalidExpression(format!(
+			"{name}: min {min} is greater than max {max}"
 		)));
 	}
-	let a = parts[0]
-		.trim()
-		.parse::<T>()
-		.map_err(|e| GeneratorError::ParseError(e.to_string()))?;
-	let b = parts[1]
-		.trim()
-		.parse::<T>()
-		.map_err(|e| GeneratorError::ParseError(e.to_string()))?;
-	Ok((a, b))
-}
-
-fn parse_list_args(s: &str) -> Result<(String, usize, usize), GeneratorError> {
-	let s = s.trim();
-	let mut depth = 0i32;
-	let mut comma_positions = Vec::new();
-	for (i, c) in s.char_indices() {
-		match c {
-			'(' | '[' => depth += 1,
-			')' | ']' => depth -= 1,
-			',' if depth == 0 => comma_positions.push(i),
-			_ => {}
-		}
-	}
-	if comma_positions.len() < 2 {
-		return Err(GeneratorError::ParseError(
-			"list needs (generator, min, max)".into(),
+	Ok(())
+}
+
+fn len_between(
+	name: &str,
+	min: &Expr,
+	max: &Expr,
+	cx: &mut Ctx<'_>,
+) -> Result<usize, GeneratorError> {
+	let (min, max) = (usize_arg(min, cx)?, usize_arg(max, cx)?);
+	check_range(name, min, max)?;
+	Ok(cx.rng.random_range(min..=max))
+}
+
+fn random_string(
+	name: &str,
+	alphabet: &[char],
+	min: &Expr,
+	max: &Expr,
+	cx: &mut Ctx<'_>,
+) -> Result<Value, GeneratorError> {
+	let len = len_between(name, min, max, cx)?;
+	let s: String = (0..len)
+		.map(|_| alphabet[cx.rng.random_range(0..alphabet.len())])
+		.collect();
+	Ok(Value::from(s))
+}
+
+/// Pick one option, optionally weighted. An array literal only generates
+/// the option picked; any other expression (such as `$nums`) must produce an
+/// array to pick from.
+fn choice(
+	options: &Expr,
+	weights: Option<Value>,
+	cx: &mut Ctx<'_>,
+) -> Result<Value, GeneratorError> {
+	if let Expr::Array(items) = options {
+		let idx = pick_index(items.len(), weights, cx)?;
+		return items[idx].eval(cx);
+	}
+	let values = match options.eval(cx)? {
+		Value::Array(values) => values,
+		other => {
+			return Err(GeneratorError::InvalidExpression(format!(
+				"choice needs an array of options, got {other}"
+			)));
+		}
+	};
+	let idx = pick_index(values.len(), weights, cx)?;
+	Ok(values[idx].clone())
+}
+
+fn pick_index(
+	len: usize,
+	weights: Option<Value>,
+	cx: &mut Ctx<'_>,
+) -> Result<usize, GeneratorError> {
+	if len == 0 {
+		return Err(GeneratorError::InvalidExpression(
+			"choice with empty array".into(),
 		));
 	}
-	let last = comma_positions[comma_positions.len() - 1];
-	let second_last = comma_positions[comma_positions.len() - 2];
+	match weights {
+		None => Ok(cx.rng.random_range(0..len)),
+		Some(Value::Array(weights)) if weights.len() == len => {
+			let weights = weights
+				.iter()
+				.map(|w| w.as_f64())
+				.collect::<Option<Vec<_>>>()
+				.ok_or_else(|| {
+					GeneratorError::InvalidExpression("choice weights must be numbers".into())
+				})?;
+			Ok(WeightedIndex::new(&weights)
+				.map_err(|e| GeneratorError::InvalidExpression(format!("choice weights: {e}")))?
+				.sample(&mut *cx.rng))
+		}
+		Some(other) => Err(GeneratorError::InvalidExpression(format!(
+			"choice needs {len} weights, got {other}"
+		))),
+	}
+}
 
-	let gen_expr = s[..second_last].trim().to_string();
-	let min: usize = s[second_last + 1..last]
-		.trim()
-		.parse()
-		.map_err(|e: std::num::ParseIntError| GeneratorError::ParseError(e.to_string()))?;
-	let max: usize = s[last + 1..]
-		.trim()
-		.parse()
-		.map_err(|e: std::num::ParseIntError| GeneratorError::ParseError(e.to_string()))?;
-	Ok((gen_expr, min, max))
+/// `len` distinct values from `item`, giving up when the generator keeps
+/// repeating itself (e.g. `unique_list(int(0, 3), 10, 10)`).
+fn distinct(
+	name: &str,
+	item: &Expr,
+	len: usize,
+	cx: &mut Ctx<'_>,
+) -> Result<Vec<Value>, GeneratorError> {
+	let max_draws = 100 + 20 * len;
+	let mut items: Vec<Value> = Vec::with_capacity(len);
+	let mut draws = 0;
+	while items.len() < len {
+		if draws == max_draws {
+			return Err(GeneratorError::InvalidExpression(format!(
+				"{name}: only {} distinct values after {draws} draws, {len} needed",
+				items.len()
+			)));
+		}
+		let value = item.eval(cx)?;
+		if !items.contains(&value) {
+			items.push(value);
+		}
+		draws += 1;
+	}
+	Ok(items)
+}
+
+/// Order for `sorted_list`: numbers by value, strings and arrays
+/// lexicographically, mixed types by type.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+	fn rank(v: &Value) -> u8 {
+		match v {
+			Value::Null => 0,
+			Value::Bool(_) => 1,
+			Value::Number(_) => 2,
+			Value::String(_) => 3,
+			Value::Array(_) => 4,
+			Value::Object(_) => 5,
+		}
+	}
+	match (a, b) {
+		(Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+		(Value::Number(x), Value::Number(y)) => x
+			.as_f64()
+			.partial_cmp(&y.as_f64())
+			.unwrap_or(Ordering::Equal),
+		(Value::String(x), Value::String(y)) => x.cmp(y),
+		(Value::Array(x), Value::Array(y)) => x
+			.iter()
+			.zip(y)
+			.map(|(x, y)| compare_values(x, y))
+			.find(|o| o.is_ne())
+			.unwrap_or_else(|| x.len().cmp(&y.len())),
+		_ => rank(a).cmp(&rank(b)),
+	}
+}
+
+/// Append random UTF-8 bytes matching `hir`. Characters come from the
+/// printable ASCII part of each class when it has one, so `\w` and `.` stay
+/// readable. A class that matches nothing (`[^\s\S]`) is an error.
+fn generate_match(hir: &Hir, rng: &mut StdRng, out: &mut Vec<u8>) -> Result<(), GeneratorError> {
+	let empty_class = || {
+		GeneratorError::InvalidExpression("str: pattern has a class that matches nothing".into())
+	};
+	match hir.kind() {
+		HirKind::Empty | HirKind::Look(_) => {}
+		HirKind::Literal(lit) => out.extend_from_slice(&lit.0),
+		HirKind::Class(Class::Unicode(class)) => {
+			let mut printable = class.clone();
+			printable.intersect(&ClassUnicode::new([ClassUnicodeRange::new(' ', '~')]));
+			let class = if printable.ranges().is_empty() {
+				class
+			} else {
+				&printable
+			};
+			let total: u32 = class
+				.ranges()
+				.iter()
+				.map(|r| r.end() as u32 - r.start() as u32 + 1)
+				.sum();
+			if total == 0 {
+				return Err(empty_class());
+			}
+			let mut idx = rng.random_range(0..total);
+			for range in class.ranges() {
+				let size = range.end() as u32 - range.start() as u32 + 1;
+				if idx < size {
+					// Ranges spanning the surrogate gap fall back to the range start
+					let c = char::from_u32(range.start() as u32 + idx).unwrap_or(range.start());
+					out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+					break;
+				}
+				idx -= size;
+			}
+		}
+		HirKind::Class(Class::Bytes(class)) => {
+			let ranges = class.ranges();
+			if ranges.is_empty() {
+				return Err(empty_class());
+			}
+			let range = &ranges[rng.random_range(0..ranges.len())];
+			out.push(rng.random_range(range.start()..=range.end()));
+		}
+		HirKind::Repetition(rep) => {
+			let max = rep.max.unwrap_or(rep.min + UNBOUNDED_REPEAT);
+			for _ in 0..rng.random_range(rep.min..=max) {
+				generate_match(&rep.sub, rng, out)?;
+			}
+		}
+		HirKind::Capture(cap) => generate_match(&cap.sub, rng, out)?,
+		HirKind::Concat(parts) => {
+			for part in parts {
+				generate_match(part, rng, out)?;
+			}
+		}
+		HirKind::Alternation(alts) => {
+			generate_match(&alts[rng.random_range(0..alts.len())], rng, out)?;
+		}
+	}
+	Ok(())
 }
 
 #[cfg(test)]
@@ -156,6 +745,7 @@ mod tests {
 	#[test]
 	fn test_float_generator() {
 		let mut rng = seeded_rng();
+		assert!(generate_value("float(-1e308, 1e308)", &mut rng).is_err());
 		let val = generate_value("float(0.0, 1.0)", &mut rng).unwrap();
 		let f = val.as_f64().unwrap();
 		assert!((0.0..=1.0).contains(&f));
@@ -210,4 +800,164 @@ mod tests {
 		let mut rng = seeded_rng();
 		assert!(generate_value("invalid()", &mut rng).is_err());
 	}
+
+	#[test]
+	fn test_str_charset_and_regex() {
+		let mut rng = seeded_rng();
+		let re = regex::Regex::new(r"^[A-Z][a-z]{2,4}-[0-9]{3}$").unwrap();
+		for _ in 0..20 {
+			let val = generate_value(r#"str(4, 4, "ab")"#, &mut rng).unwrap();
+			let s = val.as_str().unwrap();
+			assert_eq!(s.len(), 4);
+			assert!(s.chars().all(|c| c == 'a' || c == 'b'));
+
+			let val = generate_value(r"str('[A-Z][a-z]{2,4}-\d{3}')", &mut rng).unwrap();
+			assert!(re.is_match(val.as_str().unwrap()), "{val}");
+
+			let val = generate_value(r"str('(?-u:[a-c])é')", &mut rng).unwrap();
+			assert!(["aé", "bé", "cé"].contains(&val.as_str().unwrap()), "{val}");
+		}
+		for empty in [r"str('[^\s\S]')", r"str('(?-u:[a&&b])')"] {
+			let err = generate_value(empty, &mut rng).unwrap_err();
+			assert!(err.to_string().contains("matches nothing"), "{err}");
+		}
+	}
+
+	#[test]
+	fn test_weighted_choice() {
+		let mut rng = seeded_rng();
+		for _ in 0..20 {
+			let val = generate_value(r#"choice(["never", "always"], [0, 1])"#, &mut rng).unwrap();
+			assert_eq!(val, "always");
+		}
+		let val = generate_value("choice([int(5, 5), {\"k\": bool()}])", &mut rng).unwrap();
+		assert!(val == 5 || val["k"].is_boolean());
+		assert!(generate_value("choice([1, 2], [1])", &mut rng).is_err());
+	}
+
+	#[test]
+	fn test_unique_and_sorted_lists() {
+		let mut rng = seeded_rng();
+		let val = generate_value("unique_list(int(0, 9), 10, 10)", &mut rng).unwrap();
+		let mut nums: Vec<i64> = val
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|v| v.as_i64().unwrap())
+			.collect();
+		nums.sort();
+		assert_eq!(nums, (0..10).collect::<Vec<_>>());
+		assert!(generate_value("unique_list(int(0, 3), 5, 5)", &mut rng).is_err());
+
+		let val = generate_value("sorted_list(float(-5, 5), 8, 8)", &mut rng).unwrap();
+		let floats: Vec<f64> = val
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|v| v.as_f64().unwrap())
+			.collect();
+		assert!(floats.windows(2).all(|w| w[0] <= w[1]));
+	}
+
+	#[test]
+	fn test_tuple_dict_and_matrix() {
+		let mut rng = seeded_rng();
+		let val = generate_value("tuple(int(1, 1), str(2, 2), [true, null])", &mut rng).unwrap();
+		let items = val["$tuple"].as_array().unwrap();
+		assert_eq!(items[0], 1);
+		assert_eq!(items[1].as_str().unwrap().len(), 2);
+		assert_eq!(items[2], serde_json::json!([true, null]));
+
+		let val = generate_value("dict(int(0, 4), bool(), 5, 5)", &mut rng).unwrap();
+		let map = val.as_object().unwrap();
+		assert_eq!(map.len(), 5);
+		assert!(
+			map.keys()
+				.all(|k| ["0", "1", "2", "3", "4"].contains(&k.as_str()))
+		);
+
+		let val = generate_value("matrix(int(0, 9), int(2, 2), 3)", &mut rng).unwrap();
+		let rows = val.as_array().unwrap();
+		assert_eq!(rows.len(), 2);
+		assert!(rows.iter().all(|r| r.as_array().unwrap().len() == 3));
+	}
+
+	#[test]
+	fn test_parse_nested_calls_and_literals() {
+		assert_eq!(
+			parse(r#"list(choice(["a,b", 'c)']), 1, 2)"#).unwrap(),
+			Expr::Call(
+				"list".into(),
+				vec![
+					Expr::Call(
+						"choice".into(),
+						vec![Expr::Array(vec![
+							Expr::Literal("a,b".into()),
+							Expr::Literal("c)".into()),
+						])]
+					),
+					Expr::Literal(1.into()),
+					Expr::Literal(2.into()),
+				]
+			)
+		);
+		assert_eq!(
+			parse("float(-1.5e2, +3)").unwrap(),
+			Expr::Call(
+				"float".into(),
+				vec![Expr::Literal((-150.0).into()), Expr::Literal(3.into())]
+			)
+		);
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		let mut rng = seeded_rng();
+		let err = generate_value("int(0, 10", &mut rng).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"parse error in generator: missing ')' at column 10 of `int(0, 10`"
+		);
+		let err = generate_value("int(0, 10) x", &mut rng).unwrap_err();
+		assert!(
+			err.to_string().contains("unexpected 'x' at column 12"),
+			"{err}"
+		);
+		let err = generate_value("int(10, 0)", &mut rng).unwrap_err();
+		assert!(
+			err.to_string().contains("min 10 is greater than max 0"),
+			"{err}"
+		);
+		let err = generate_value("int(1, 2, 3)", &mut rng).unwrap_err();
+		assert!(
+			err.to_string().contains("int does not take 3 arguments"),
+			"{err}"
+		);
+		assert!(generate_value("int(0, 1.5)", &mut rng).is_err());
+	}
+
+	#[test]
+	fn test_refs_and_arithmetic() {
+		let expr = parse("int(0, len(nums) - 1) * 2 + $k").unwrap();
+		assert_eq!(expr.refs(), ["nums", "k"]);
+
+		let mut bindings = Bindings::new();
+		bindings.insert("nums".into(), serde_json::json!([7, 8, 9]));
+		bindings.insert("k".into(), serde_json::json!(100));
+		let mut rng = seeded_rng();
+		for _ in 0..20 {
+			let n = expr
+				.generate(&bindings, &mut rng)
+				.unwrap()
+				.as_i64()
+				.unwrap();
+			assert!([100, 102, 104].contains(&n), "{n}");
+		}
+		let picked = parse("choice(nums)")
+			.unwrap()
+			.generate(&bindings, &mut rng)
+			.unwrap();
+		assert!([7, 8, 9].contains(&picked.as_i64().unwrap()));
+		assert!(generate_value("len(nums)", &mut rng).is_err());
+	}
 }
@@ -0,0 +1,924 @@
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use regex::Regex;
+
+use crate::models::{CaseResult, FailureDetail, StudentFile, TestCase, TestSpec, TestStatus};
+use crate::runner::compiled::{BuildCache, BuildFailure, build_sandbox, run_build};
+use crate::runner::evaluate::{check_expected_files, evaluate_function_result, evaluate_io_output};
+use crate::runner::executor::{BoxFuture, Executor};
+use crate::runner::process::{
+	CaseDir, SpawnError, find_student_file, inputs_as_stdin, missing_file_result,
+	resolve_program_path, sandboxed_command, setup_error_result, spawn_with_timeout,
+};
+use crate::runner::sandbox::SandboxConfig;
+
+/// Harness class compiled next to every submission.
+///
+/// Takes a JSON payload on argv[0] with `classes`, `function`, `args`, calls
+/// the first static method that matches (exact name, then case-insensitive)
+/// and accepts the decoded args, and prints the same envelope as the Python
+/// helper. What the method prints is captured into the envelope's `stdout`.
+const HARNESS_CLASS: &str = "ScriptMarkHarness";
+const HARNESS_SOURCE: &str = r#"
+import java.io.ByteArrayOutputStream;
+import java.io.PrintStream;
+import java.lang.reflect.Array;
+import java.nio.charset.StandardCharsets;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+import java.lang.reflect.Modifier;
+import java.lang.reflect.ParameterizedType;
+import java.lang.reflect.Type;
+import java.util.ArrayList;
+import java.util.Collection;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+/** Calls a static method with JSON-decoded args and prints a JSON envelope. */
+public final class ScriptMarkHarness {
+	public static void main(String[] argv) {
+		PrintStream out = System.out;
+		ByteArrayOutputStream printed = new ByteArrayOutputStream();
+		String fuzzy = "";
+		try {
+			@SuppressWarnings("unchecked")
+			Map<String, Object> payload = (Map<String, Object>) new Parser(argv[0]).parse();
+			@SuppressWarnings("unchecked")
+			List<Object> classes = (List<Object>) payload.get("classes");
+			String function = (String) payload.get("function");
+			@SuppressWarnings("unchecked")
+			List<Object> args = (List<Object>) payload.get("args");
+
+			Object[] call = new Object[args.size()];
+			Method method = find(classes, function, args, call);
+			if (method == null) {
+				out.println(error("NoSuchMethodException", "Function '" + function + "' not found"));
+				return;
+			}
+			if (!method.getName().equals(function)) {
+				fuzzy = ",\"matched_name\":" + quote(method.getName());
+			}
+			method.setAccessible(true);
+			System.setOut(new PrintStream(printed, true, StandardCharsets.UTF_8));
+			Object result;
+			try {
+				result = method.invoke(null, call);
+			} finally {
+				System.setOut(out);
+			}
+			String type = method.getReturnType() == void.class
+				? "void"
+				: result == null ? "null" : result.getClass().getSimpleName();
+			out.println("{\"ok\":true,\"value\":" + toJson(result) + ",\"type\":" + quote(type)
+				+ ",\"stdout\":" + quote(printed.toString(StandardCharsets.UTF_8)) + fuzzy + "}");
+		} catch (InvocationTargetException e) {
+			System.setOut(out);
+			Throwable cause = e.getCause() == null ? e : e.getCause();
+			out.println(error(cause.getClass().getSimpleName(), cause.getMessage(),
+				printed.toString(StandardCharsets.UTF_8), fuzzy));
+		} catch (Throwable e) {
+			System.setOut(out);
+			out.println(error(e.getClass().getSimpleName(), e.getMessage()));
+		}
+		out.flush();
+	}
+
+	/** Static method by exact name, then case-insensitive, whose params accept the args. */
+	private static Method find(List<Object> classes, String name, List<Object> args, Object[] call) {
+		for (int pass = 0; pass < 2; pass++) {
+			for (Object className : classes) {
+				Class<?> cls;
+				try {
+					cls = Class.forName((String) className);
+				} catch (ClassNotFoundException | LinkageError e) {
+					// Nested types and names from string literals don't load by simple name
+					continue;
+				}
+				for (Method m : cls.getDeclaredMethods()) {
+					boolean named = pass == 0 ? m.getName().equals(name) : m.getName().equalsIgnoreCase(name);
+					if (!named || !Modifier.isStatic(m.getModifiers()) || m.getParameterCount() != args.size()) {
+						continue;
+					}
+					try {
+						Type[] types = m.getGenericParameterTypes();
+						for (int i = 0; i < call.length; i++) {
+							call[i] = convert(args.get(i), types[i]);
+						}
+						return m;
+					} catch (IllegalArgumentException e) {
+						// args don't fit this overload, try the next one
+					}
+				}
+			}
+		}
+		return null;
+	}
+
+	private static Object convert(Object value, Type type) {
+		Class<?> raw = rawClass(type);
+		if (value == null) {
+			if (raw.isPrimitive()) {
+				throw new IllegalArgumentException("null for primitive");
+			}
+			return null;
+		}
+		if (raw == int.class || raw == Integer.class) return (int) integral(value);
+		if (raw == long.class || raw == Long.class) return integral(value);
+		if (raw == short.class || raw == Short.class) return (short) integral(value);
+		if (raw == byte.class || raw == Byte.class) return (byte) integral(value);
+		if (raw == double.class || raw == Double.class) return number(value).doubleValue();
+		if (raw == float.class || raw == Float.class) return number(value).floatValue();
+		if (raw == boolean.class || raw == Boolean.class) {
+			if (value instanceof Boolean) return value;
+			throw new IllegalArgumentException("not a boolean");
+		}
+		if (raw == char.class || raw == Character.class) {
+			if (value instanceof String s && s.length() == 1) return s.charAt(0);
+			throw new IllegalArgumentException("not a char");
+		}
+		if (raw == String.class) {
+			if (value instanceof String) return value;
+			throw new IllegalArgumentException("not a string");
+		}
+		if (raw.isArray()) {
+			List<?> list = list(value);
+			Class<?> component = raw.getComponentType();
+			Object array = Array.newInstance(component, list.size());
+			for (int i = 0; i < list.size(); i++) {
+				Array.set(array, i, convert(list.get(i), component));
+			}
+			return array;
+		}
+		if (Collection.class.isAssignableFrom(raw)) {
+			Type element = typeArg(type, 0);
+			List<Object> converted = new ArrayList<>();
+			for (Object item : list(value)) {
+				converted.add(convert(item, element));
+			}
+			if (raw.isAssignableFrom(ArrayList.class)) return converted;
+			if (raw.isAssignableFrom(java.util.LinkedHashSet.class)) return new java.util.LinkedHashSet<>(converted);
+			throw new IllegalArgumentException("unsupported collection " + raw.getName());
+		}
+		if (Map.class.isAssignableFrom(raw)) {
+			if (!(value instanceof Map<?, ?> map)) throw new IllegalArgumentException("not an object");
+			Type keyType = typeArg(type, 0);
+			Type valueType = typeArg(type, 1);
+			Map<Object, Object> converted = new LinkedHashMap<>();
+			for (Map.Entry<?, ?> e : map.entrySet()) {
+				Object key = e.getKey();
+				Class<?> keyRaw = rawClass(keyType);
+				if (keyRaw != String.class && keyRaw != Object.class) {
+					key = convert(Parser.number((String) key), keyType);
+				}
+				converted.put(key, convert(e.getValue(), valueType));
+			}
+			if (raw.isAssignableFrom(LinkedHashMap.class)) return converted;
+			throw new IllegalArgumentException("unsupported map " + raw.getName());
+		}
+		if (raw == Object.class || raw == Number.class && value instanceof Number) return value;
+		throw new IllegalArgumentException("unsupported parameter type " + raw.getName());
+	}
+
+	private static Class<?> rawClass(Type type) {
+		if (type instanceof Class<?> c) return c;
+		if (type instanceof ParameterizedType p) return (Class<?>) p.getRawType();
+		return Object.class;
+	}
+
+	private static Type typeArg(Type type, int index) {
+		if (type instanceof ParameterizedType p && p.getActualTypeArguments().length > index) {
+			return p.getActualTypeArguments()[index];
+		}
+		return Object.class;
+	}
+
+	private static Number number(Object value) {
+		if (value instanceof Number n) return n;
+		throw new IllegalArgumentException("not a number");
+	}
+
+	private static long integral(Object value) {
+		Number n = number(value);
+		if (n instanceof Double) throw new IllegalArgumentException("not an integer");
+		return n.longValue();
+	}
+
+	private static List<?> list(Object value) {
+		if (value instanceof List<?> l) return l;
+		throw new IllegalArgumentException("not an array");
+	}
+
+	private static String error(String type, String message) {
+		return error(type, message, "", "");
+	}
+
+	/** Error envelope; `extra` holds further fields, each with a leading comma. */
+	private static String error(String type, String message, String stdout, String extra) {
+		return "{\"ok\":false,\"error_type\":" + quote(type) + ",\"error_message\":"
+			+ quote(message == null ? "" : message) + ",\"stdout\":" + quote(stdout) + extra + "}";
+	}
+
+	private static String toJson(Object value) {
+		if (value == null) return "null";
+		if (value instanceof Boolean || value instanceof Integer || value instanceof Long
+			|| value instanceof Short || value instanceof Byte) {
+			return value.toString();
+		}
+		if (value instanceof Number n) {
+			double d = n.doubleValue();
+			return Double.isFinite(d) ? Double.toString(d) : "null";
+		}
+		if (value instanceof String || value instanceof Character) return quote(value.toString());
+		if (value.getClass().isArray()) {
+			StringBuilder sb = new StringBuilder("[");
+			for (int i = 0; i < Array.getLength(value); i++) {
+				if (i > 0) sb.append(',');
+				sb.append(toJson(Array.get(value, i)));
+			}
+			return sb.append(']').toString();
+		}
+		if (value instanceof Iterable<?> items) {
+			StringBuilder sb = new StringBuilder("[");
+			for (Object item : items) {
+				if (sb.length() > 1) sb.append(',');
+				sb.append(toJson(item));
+			}
+			return sb.append(']').toString();
+		}
+		if (value instanceof Map<?, ?> map) {
+			StringBuilder sb = new StringBuilder("{");
+			for (Map.Entry<?, ?> e : map.entrySet()) {
+				if (sb.length() > 1) sb.append(',');
+				sb.append(quote(String.valueOf(e.getKey()))).append(':').append(toJson(e.getValue()));
+			}
+			return sb.append('}').toString();
+		}
+		return quote(value.toString());
+	}
+
+	private static String quote(String s) {
+		StringBuilder sb = new StringBuilder("\"");
+		for (char c : s.toCharArray()) {
+			switch (c) {
+				case '"' -> sb.append("\\\"");
+				case '\\' -> sb.append("\\\\");
+				case '\n' -> sb.append("\\n");
+				case '\r' -> sb.append("\\r");
+				case '\t' -> sb.append("\\t");
+				default -> {
+					if (c < 0x20) sb.append(String.format("\\u%04x", (int) c));
+					else sb.append(c);
+				}
+			}
+		}
+		return sb.append('"').toString();
+	}
+
+	/** Minimal JSON reader: objects, arrays, strings, numbers, booleans, null. */
+	private static final class Parser {
+		private final String src;
+		private int pos;
+
+		Parser(String src) {
+			this.src = src;
+		}
+
+		Object parse() {
+			skip();
+			char c = src.charAt(pos);
+			switch (c) {
+				case '{': {
+					pos++;
+					Map<String, Object> map = new LinkedHashMap<>();
+					skip();
+					if (src.charAt(pos) == '}') { pos++; return map; }
+					while (true) {
+						skip();
+						String key = (String) parse();
+						skip();
+						pos++; // ':'
+						map.put(key, parse());
+						skip();
+						if (src.charAt(pos++) == '}') return map;
+					}
+				}
+				case '[': {
+					pos++;
+					List<Object> list = new ArrayList<>();
+					skip();
+					if (src.charAt(pos) == ']') { pos++; return list; }
+					while (true) {
+						list.add(parse());
+						skip();
+						if (src.charAt(pos++) == ']') return list;
+					}
+				}
+				case '"': {
+					pos++;
+					StringBuilder sb = new StringBuilder();
+					while (true) {
+						char ch = src.charAt(pos++);
+						if (ch == '"') return sb.toString();
+						if (ch != '\\') { sb.append(ch); continue; }
+						char esc = src.charAt(pos++);
+						switch (esc) {
+							case 'n' -> sb.append('\n');
+							case 't' -> sb.append('\t');
+							case 'r' -> sb.append('\r');
+							case 'b' -> sb.append('\b');
+							case 'f' -> sb.append('\f');
+							case 'u' -> { sb.append((char) Integer.parseInt(src.substring(pos, pos + 4), 16)); pos += 4; }
+							default -> sb.append(esc);
+						}
+					}
+				}
+				default: {
+					int start = pos;
+					while (pos < src.length() && ",]} \t\r\n".indexOf(src.charAt(pos)) < 0) pos++;
+					String token = src.substring(start, pos);
+					return switch (token) {
+						case "true" -> Boolean.TRUE;
+						case "false" -> Boolean.FALSE;
+						case "null" -> null;
+						default -> number(token);
+					};
+				}
+			}
+		}
+
+		static Number number(String token) {
+			if (token.contains(".") || token.contains("e") || token.contains("E")) {
+				return Double.parseDouble(token);
+			}
+			return Long.parseLong(token);
+		}
+
+		private void skip() {
+			while (pos < src.length() && Character.isWhitespace(src.charAt(pos))) pos++;
+		}
+	}
+}
+"#;
+
+/// JVM flags that keep the reserved address space small enough for `RLIMIT_AS`.
+const JVM_FLAGS: &[&str] = &[
+	"-Xmx256m",
+	"-Xss8m",
+	"-XX:ReservedCodeCacheSize=64m",
+	"-XX:CompressedClassSpaceSize=64m",
+	"-XX:+UseSerialGC",
+	"-XX:TieredStopAtLevel=1",
+];
+
+static PACKAGE_RE: LazyLock<Regex> =
+	LazyLock::new(|| Regex::new(r"(?m)^\s*package\s+([\w.]+)\s*;").unwrap());
+static PUBLIC_CLASS_RE: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(
+		r"\bpublic\s+(?:(?:final|abstract|sealed)\s+)*(?:class|enum|record|interface)\s+(\w+)",
+	)
+	.unwrap()
+});
+static CLASS_RE: LazyLock<Regex> =
+	LazyLock::new(|| Regex::new(r"\b(?:class|enum|record|interface)\s+(\w+)").unwrap());
+static MAIN_RE: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(r"\bstatic\s+(?:final\s+)?void\s+main\s*\(\s*(?:final\s+)?String").unwrap()
+});
+static COMMENT_RE: LazyLock<Regex> =
+	LazyLock::new(|| Regex::new(r"(?s)/\*.*?\*/|//[^\n]*").unwrap());
+
+/// What `javac` produced for one submission.
+#[derive(Debug, Clone)]
+struct JavaBuild {
+	classes_dir: PathBuf,
+	/// Fully qualified class names, in declaration order.
+	classes: Vec<String>,
+	/// Class declaring `main(String[])`, if any.
+	main_class: Option<String>,
+}
+
+/// Class names and main class found in a Java source file.
+#[derive(Debug, PartialEq)]
+struct SourceInfo {
+	package: Option<String>,
+	public_class: Option<String>,
+	classes: Vec<String>,
+	main_class: Option<String>,
+}
+
+impl SourceInfo {
+	fn parse(source: &str) -> Self {
+		let code = COMMENT_RE.replace_all(source, "");
+		let package = PACKAGE_RE.captures(&code).map(|c| c[1].to_string());
+		let public_class = PUBLIC_CLASS_RE.captures(&code).map(|c| c[1].to_string());
+		let declared: Vec<(usize, String)> = CLASS_RE
+			.captures_iter(&code)
+			.map(|c| (c.get(0).unwrap().start(), c[1].to_string()))
+			.collect();
+		// The class declared last before `main` is the one that holds it
+		let main_class = MAIN_RE.find(&code).and_then(|m| {
+			declared
+				.iter()
+				.rev()
+				.find(|(pos, _)| *pos < m.start())
+				.map(|(_, name)| name.clone())
+		});
+		let mut classes: Vec<String> = Vec::new();
+		for (_, name) in declared {
+			if !classes.contains(&name) {
+				classes.push(name);
+			}
+		}
+		Self {
+			package,
+			public_class,
+			classes,
+			main_class,
+		}
+	}
+
+	fn qualify(&self, name: &str) -> String {
+		match &self.package {
+			Some(pkg) if !name.contains('.') => format!("{pkg}.{name}"),
+			_ => name.to_string(),
+		}
+	}
+}
+
+/// Executor for Java submissions.
+///
+/// Each student file is compiled once with `javac` into its own scratch
+/// directory, together with a harness class. IO cases run the class holding
+/// `main` (or `TestMeta.main_class`); function-call cases go through the
+/// harness, which calls a static method with the JSON-decoded `args`.
+pub struct JavaExecutor {
+	javac: String,
+	java: String,
+	compile_timeout_secs: u64,
+	sandbox: SandboxConfig,
+	builds: BuildCache<JavaBuild>,
+	python_cmd: String,
+}
+
+impl JavaExecutor {
+	pub fn new() -> Self {
+		Self {
+			javac: resolve_program_path("javac"),
+			java: resolve_program_path("java"),
+			compile_timeout_secs: 120,
+			// The JVM reserves far more address space and threads than it uses
+			sandbox: SandboxConfig {
+				mem_mb: 2048,
+				nproc: 512,
+				..SandboxConfig::default()
+			},
+			builds: BuildCache::new(),
+			python_cmd: resolve_program_path("python3"),
+		}
+	}
+
+	/// Timeout for a single `javac` run, in seconds. Default: 120.
+	pub fn with_compile_timeout(mut self, secs: u64) -> Self {
+		self.compile_timeout_secs = secs;
+		self
+	}
+
+	/// Python interpreter for Python verifier checks. Default: `python3`.
+	pub fn with_python_cmd(mut self, python_cmd: impl Into<String>) -> Self {
+		self.python_cmd = resolve_program_path(&python_cmd.into());
+		self
+	}
+
+	/// Compile once per source file, returning the cached class directory.
+	async fn build(&self, source: &Path) -> Result<JavaBuild, BuildFailure> {
+		self.builds
+			.get_or_build(source, "javac", |build_dir| async move {
+				let code = std::fs::read_to_string(source).map_err(|e| BuildFailure {
+					status: TestStatus::Error,
+					message: format!("Failed to read {}: {e}", source.display()),
+					details: String::new(),
+				})?;
+				let info = SourceInfo::parse(&code);
+
+				// javac insists that a public class lives in `<Name>.java`
+				let src_dir = build_dir.join("src");
+				let classes_dir = build_dir.join("classes");
+				let file_name = match &info.public_class {
+					Some(name) => format!("{name}.java"),
+					None => source
+						.file_name()
+						.map(|n| n.to_string_lossy().into_owned())
+						.unwrap_or_else(|| "Main.java".to_string()),
+				};
+				let write = |path: PathBuf, contents: &str| {
+					std::fs::write(&path, contents).map_err(|e| BuildFailure {
+						status: TestStatus::Error,
+						message: format!("Failed to write {}: {e}", path.display()),
+						details: String::new(),
+					})
+				};
+				let _ = std::fs::create_dir_all(&src_dir);
+				write(src_dir.join(&file_name), &code)?;
+				write(
+					src_dir.join(format!("{HARNESS_CLASS}.java")),
+					HARNESS_SOURCE,
+				)?;
+
+				let argv: Vec<String> = [
+					self.javac.as_str(),
+					"-J-Xmx512m",
+					"-encoding",
+					"UTF-8",
+					"-nowarn",
+					"-d",
+				]
+				.into_iter()
+				.map(String::from)
+				.chain([
+					classes_dir.to_string_lossy().into_owned(),
+					src_dir.join(&file_name).to_string_lossy().into_owned(),
+					src_dir
+						.join(format!("{HARNESS_CLASS}.java"))
+						.to_string_lossy()
+						.into_owned(),
+				])
+				.collect();
+				run_build(
+					&argv,
+					&build_dir,
+					&[],
+					&build_sandbox(),
+					self.compile_timeout_secs,
+				)
+				.await?;
+
+				Ok(JavaBuild {
+					classes_dir,
+					classes: info.classes.iter().map(|c| info.qualify(c)).collect(),
+					main_class: info.main_class.as_deref().map(|c| info.qualify(c)),
+				})
+			})
+			.await
+	}
+
+	fn java_cmd(&self, build: &JavaBuild) -> tokio::process::Command {
+		let mut cmd = sandboxed_command(&self.java, &self.sandbox);
+		cmd.args(JVM_FLAGS).arg("-cp").arg(&build.classes_dir);
+		cmd
+	}
+
+	/// Run a static method through the harness.
+	async fn execute_function_call(
+		&self,
+		build: &JavaBuild,
+		preferred_class: Option<&str>,
+		function_name: &str,
+		case: &TestCase,
+		timeout_secs: u64,
+	) -> CaseResult {
+		if !case.kwargs.is_empty() {
+			return setup_error_result(
+				case,
+				"Keyword arguments are not supported for java".to_string(),
+			);
+		}
+		let start = Instant::now();
+
+		// Search the requested class first, then the rest in source order
+		let mut classes: Vec<&str> = preferred_class.into_iter().collect();
+		classes.extend(
+			build
+				.classes
+				.iter()
+				.map(|c| c.as_str())
+				.filter(|c| Some(*c) != preferred_class),
+		);
+		let payload = serde_json::json!({
+			"classes": classes,
+			"function": function_name,
+			"args": case.args,
+		});
+
+		let case_dir = match CaseDir::new(case) {
+			Ok(dir) => dir,
+			Err(e) => return setup_error_result(case, e),
+		};
+		let mut cmd = self.java_cmd(build);
+		cmd.arg(HARNESS_CLASS).arg(payload.to_string());
+		case_dir.apply_function(&mut cmd, case);
+
+		let stdin = inputs_as_stdin(case);
+		let result = spawn_with_timeout(cmd, stdin.as_deref(), timeout_secs).await;
+		let elapsed = start.elapsed().as_millis() as u64;
+
+		match result {
+			Err(SpawnError::Timeout) => CaseResult {
+				case_name: case.name.clone(),
+				status: TestStatus::Timeout,
+				actual: None,
+				expected: case.expect.as_ref().map(|v| v.to_string()),
+				failure: Some(FailureDetail {
+					message: format!("Timed out after {timeout_secs}s"),
+					details: String::new(),
+				}),
+				elapsed_ms: Some(elapsed),
+				matched_name: None,
+			},
+			Err(SpawnError::Spawn(e)) => CaseResult {
+				case_name: case.name.clone(),
+				status: TestStatus::Error,
+				actual: None,
+				expected: None,
+				failure: Some(FailureDetail {
+					message: format!("Failed to spawn java: {e}"),
+					details: String::new(),
+				}),
+				elapsed_ms: Some(elapsed),
+				matched_name: None,
+			},
+			Ok(output) => {
+				let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+				if stdout.is_empty() {
+					// The JVM died before the harness could answer (e.g. OOM, stack overflow in a static initializer)
+					return CaseResult {
+						case_name: case.name.clone(),
+						status: TestStatus::Error,
+						actual: None,
+						expected: case.expect.as_ref().map(|v| v.to_string()),
+						failure: Some(FailureDetail {
+							message: format!("java exited without a result ({})", output.status),
+							details: String::from_utf8_lossy(&output.stderr)
+								.trim_end()
+								.to_string(),
+						}),
+						elapsed_ms: Some(elapsed),
+						matched_name: None,
+					};
+				}
+				let result = evaluate_function_result(&stdout, case, elapsed, &self.python_cmd);
+				check_expected_files(result, case, case_dir.path(), &self.python_cmd)
+			}
+		}
+	}
+
+	async fn run_case(
+		&self,
+		student_files: &[StudentFile],
+		spec: &TestSpec,
+		case: &TestCase,
+		timeout_secs: u64,
+	) -> CaseResult {
+		let function = case.function.as_ref().or(spec.meta.function.as_ref());
+		let needle = function.map(|f| format!(" {f}("));
+		let Some(student_file) =
+			find_student_file(student_files, &spec.meta.file, needle.as_deref())
+		else {
+			return missing_file_result(case, &spec.meta.file);
+		};
+
+		let build = match self.build(&student_file.path).await {
+			Ok(b) => b,
+			Err(failure) => return failure.case_result(case),
+		};
+		let main_class = spec
+			.meta
+			.main_class
+			.as_deref()
+			.map(|name| {
+				// Qualify a bare name with the package of the compiled classes
+				build
+					.classes
+					.iter()
+					.find(|c| c.rsplit('.').next() == Some(name))
+					.cloned()
+					.unwrap_or_else(|| name.to_string())
+			})
+			.or_else(|| build.main_class.clone());
+
+		if let Some(function) = function {
+			return self
+				.execute_function_call(&build, main_class.as_deref(), function, case, timeout_secs)
+				.await;
+		}
+
+		let Some(main_class) = main_class else {
+			return CaseResult {
+				case_name: case.name.clone(),
+				status: TestStatus::Error,
+				actual: None,
+				expected: case.expected_stdout.clone(),
+				failure: Some(FailureDetail {
+					message: "No class with `public static void main(String[])` found; set meta.main_class"
+						.to_string(),
+					details: String::new(),
+				}),
+				elapsed_ms: Some(0),
+				matched_name: None,
+			};
+		};
+
+		let start = Instant::now();
+		let mut cmd = self.java_cmd(&build);
+		cmd.arg(&main_class);
+		let case_dir = match CaseDir::new(case) {
+			Ok(dir) => dir,
+			Err(e) => return setup_error_result(case, e),
+		};
+		case_dir.apply_io(&mut cmd, case);
+		let result = spawn_with_timeout(cmd, case.stdin.as_deref(), timeout_secs).await;
+		let result = evaluate_io_output(result, case, timeout_secs, start, &self.python_cmd);
+		check_expected_files(result, case, case_dir.path(), &self.python_cmd)
+	}
+}
+
+impl Default for JavaExecutor {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Executor for JavaExecutor {
+	fn language(&self) -> &str {
+		"java"
+	}
+
+	fn python_cmd(&self) -> &str {
+		&self.python_cmd
+	}
+
+	fn execute_case<'a>(
+		&'a self,
+		student_files: &'a [StudentFile],
+		spec: &'a TestSpec,
+		case: &'a TestCase,
+		timeout_secs: u64,
+	) -> BoxFuture<'a, CaseResult> {
+		Box::pin(self.run_case(student_files, spec, case, timeout_secs))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn spec(toml_str: &str) -> TestSpec {
+		toml::from_str(toml_str).unwrap()
+	}
+
+	fn java_file(dir: &Path, name: &str, code: &str) -> Vec<StudentFile> {
+		let path = dir.join(name);
+		std::fs::write(&path, code).unwrap();
+		vec![StudentFile {
+			path,
+			language: "java".to_string(),
+		}]
+	}
+
+	#[test]
+	fn test_source_info_detects_main_class_and_package() {
+		let info = SourceInfo::parse(
+			"package lab.one;\n// class Fake {}\nclass Helper {}\npublic final class Lab1 {\n  public static void main(String[] args) {}\n}\n",
+		);
+		assert_eq!(info.public_class.as_deref(), Some("Lab1"));
+		assert_eq!(info.classes, vec!["Helper", "Lab1"]);
+		assert_eq!(info.main_class.as_deref(), Some("Lab1"));
+		assert_eq!(info.qualify("Lab1"), "lab.one.Lab1");
+	}
+
+	#[tokio::test]
+	async fn test_io_cases_run_detected_main() {
+		let dir = tempfile::tempdir().unwrap();
+		// File name differs from the public class; the executor must rename it
+		let files = java_file(
+			dir.path(),
+			"alice_Echo.java",
+			"import java.util.Scanner;\nclass Util { static int twice(int n) { return 2 * n; } }\npublic class Echo {\n  public static void main(String[] args) {\n    int n = new Scanner(System.in).nextInt();\n    System.out.println(Util.twice(n));\n  }\n}\n",
+		);
+		let spec = spec(
+			r#"
+[meta]
+name = "echo"
+file = "Echo.java"
+language = "java"
+
+[[cases]]
+name = "21"
+stdin = "21\n"
+expected_stdout = "42\n"
+"#,
+		);
+		let executor = JavaExecutor::new();
+		let results = executor.execute_chain(&files, &spec, &spec.cases, 20).await;
+		assert_eq!(results[0].status, TestStatus::Passed, "{:?}", results[0]);
+	}
+
+	#[tokio::test]
+	async fn test_function_cases_call_static_methods() {
+		let dir = tempfile::tempdir().unwrap();
+		let files = java_file(
+			dir.path(),
+			"Lab2.java",
+			"import java.util.*;\npublic class Lab2 {\n  static List<Integer> sortDesc(int[] xs) {\n    System.out.println(\"debug\");\n    List<Integer> r = new ArrayList<>();\n    for (int x : xs) r.add(x);\n    r.sort(Collections.reverseOrder());\n    return r;\n  }\n  static int div(int a, int b) { return a / b; }\n}\n",
+		);
+		let spec = spec(
+			r#"
+[meta]
+name = "lab2"
+file = "Lab2.java"
+language = "java"
+
+[[cases]]
+name = "sort"
+function = "sortDesc"
+args = [[3, 1, 2]]
+expect = [3, 2, 1]
+expected_stdout = "debug\n"
+
+[[cases]]
+name = "div by zero"
+function = "div"
+args = [1, 0]
+expect_error = "ArithmeticException"
+
+[[cases]]
+name = "missing"
+function = "nope"
+args = []
+expect = 0
+"#,
+		);
+		let executor = JavaExecutor::new();
+		let results = executor.execute_chain(&files, &spec, &spec.cases, 20).await;
+		assert_eq!(results[0].status, TestStatus::Passed, "{:?}", results[0]);
+		assert_eq!(results[1].status, TestStatus::Passed, "{:?}", results[1]);
+		assert_eq!(results[2].status, TestStatus::Failed);
+		assert!(
+			results[2]
+				.actual
+				.as_deref()
+				.unwrap()
+				.starts_with("NoSuchMethodException")
+		);
+	}
+
+	#[tokio::test]
+	async fn test_function_lookup_skips_nested_classes() {
+		let dir = tempfile::tempdir().unwrap();
+		let files = java_file(
+			dir.path(),
+			"Lists.java",
+			"public class Lists {\n  static class Node { int value; Node next; }\n  static String label = \"class Ghost\";\n  static int findmax(int[] xs) {\n    int best = xs[0];\n    for (int x : xs) best = Math.max(best, x);\n    return best;\n  }\n}\n",
+		);
+		let spec = spec(
+			r#"
+[meta]
+name = "lists"
+file = "Lists.java"
+language = "java"
+
+[[cases]]
+name = "max"
+function = "findMax"
+args = [[3, 7, 2]]
+expect = 7
+"#,
+		);
+		let executor = JavaExecutor::new();
+		let result = executor
+			.execute_case(&files, &spec, &spec.cases[0], 20)
+			.await;
+		assert_eq!(result.status, TestStatus::Passed, "{result:?}");
+		assert_eq!(result.matched_name.as_deref(), Some("findmax"));
+	}
+
+	#[tokio::test]
+	async fn test_compile_error_carries_diagnostics() {
+		let dir = tempfile::tempdir().unwrap();
+		let files = java_file(
+			dir.path(),
+			"Broken.java",
+			"public class Broken { static int f() { return oops; } }\n",
+		);
+		let spec = spec(
+			r#"
+[meta]
+name = "broken"
+file = "Broken.java"
+language = "java"
+function = "f"
+
+[[cases]]
+name = "f"
+expect = 1
+"#,
+		);
+		let executor = JavaExecutor::new();
+		let result = executor
+			.execute_case(&files, &spec, &spec.cases[0], 20)
+			.await;
+		assert_eq!(result.status, TestStatus::CompileError);
+		assert!(result.failure.unwrap().details.contains("oops"));
+	}
+}
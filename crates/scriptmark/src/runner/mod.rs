@@ -1,9 +1,15 @@
+pub mod compiled;
+pub(crate) mod evaluate;
 pub mod executor;
 pub mod expander;
 pub mod generator;
+pub mod java;
 pub mod linter;
+pub mod node;
 pub mod oracle;
 pub mod orchestrator;
+pub(crate) mod process;
 pub mod python;
+pub mod requirements;
 pub mod resolve;
 pub mod sandbox;
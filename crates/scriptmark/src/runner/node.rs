@@ -0,0 +1,551 @@
+use std::time::Instant;
+
+use crate::models::{CaseResult, FailureDetail, StudentFile, TestCase, TestSpec, TestStatus};
+use crate::runner::evaluate::{check_expected_files, evaluate_function_result, evaluate_io_output};
+use crate::runner::executor::{BoxFuture, Executor};
+use crate::runner::process::{
+	CaseDir, SpawnError, absolute_path, find_student_file, inputs_as_stdin, missing_file_result,
+	resolve_program_path, sandboxed_command, setup_error_result, spawn_with_timeout,
+};
+use crate::runner::sandbox::SandboxConfig;
+
+/// Node helper script embedded in the binary (the JS twin of `HELPER_SCRIPT`).
+///
+/// Takes a JSON payload on argv[1] with `file`, `function`, `args`, `vars`,
+/// `allowed_imports` (or `mode: "run"` to just run the program). TypeScript is
+/// stripped with `module.stripTypeScriptTypes` or the `typescript` package.
+/// Outputs JSON on stdout: `{"ok": true, "value": ..., "type": "..."}` or
+/// `{"ok": false, "error_type": "...", "error_message": "..."}`, both with the
+/// `stdout` printed during the call.
+const HELPER_SCRIPT: &str = r##"
+const fs = require("fs");
+const path = require("path");
+const os = require("os");
+const url = require("url");
+const Module = require("module");
+
+const payload = JSON.parse(process.argv[1]);
+const filePath = path.resolve(payload.file);
+const realWrite = process.stdout.write.bind(process.stdout);
+let printed = "";
+let fuzzy = {};
+
+function emit(obj) {
+    realWrite(JSON.stringify(obj) + "\n");
+    process.exit(0);
+}
+
+function fail(errorType, message) {
+    emit({ ok: false, error_type: errorType, error_message: String(message), stdout: printed, ...fuzzy });
+}
+
+function errorName(e) {
+    if (!(e instanceof Error)) return "Error";
+    return e.name && e.name !== "Error" ? e.name : (e.constructor && e.constructor.name) || "Error";
+}
+
+// Import guard: deny process/network/VM modules unless the teacher allows them.
+// Only covers require(); ESM imports of builtins are left to the OS sandbox.
+const BLOCKED = new Set(["child_process", "cluster", "worker_threads", "net", "http", "https",
+    "http2", "dgram", "dns", "tls", "vm", "inspector", "repl"]);
+const allowed = new Set(payload.allowed_imports || []);
+const originalLoad = Module._load;
+Module._load = function (request, ...rest) {
+    const name = request.replace(/^node:/, "");
+    if (BLOCKED.has(name) && !allowed.has(name)) {
+        const e = new Error(`Import of '${request}' is not allowed`);
+        e.name = "ImportError";
+        throw e;
+    }
+    return originalLoad.call(this, request, ...rest);
+};
+
+function stripTypes(code) {
+    if (typeof Module.stripTypeScriptTypes === "function") {
+        return Module.stripTypeScriptTypes(code, { mode: "transform" });
+    }
+    for (const req of [Module.createRequire(filePath), require]) {
+        let ts;
+        try { ts = req("typescript"); } catch { continue; }
+        return ts.transpileModule(code, {
+            compilerOptions: { module: ts.ModuleKind.ESNext, target: ts.ScriptTarget.ES2022 },
+        }).outputText;
+    }
+    fail("TypeScriptUnavailable", "TypeScript needs Node >= 22.13 or the 'typescript' package");
+}
+
+async function load(code) {
+    const esm = /\.m[jt]s$/.test(filePath)
+        || (!/\.c[jt]s$/.test(filePath) && /^\s*(import\s*[\w{*"']|export\s)/m.test(code));
+    if (esm) {
+        // ESM can't be compiled from a string in-process; import a temp copy
+        const dir = fs.mkdtempSync(path.join(os.tmpdir(), "scriptmark-"));
+        const target = path.join(dir, path.basename(filePath).replace(/\.[cm]?[jt]s$/, "") + ".mjs");
+        fs.writeFileSync(target, code);
+        try {
+            return await import(url.pathToFileURL(target).href);
+        } finally {
+            fs.rmSync(dir, { recursive: true, force: true });
+        }
+    }
+    const mod = new Module(filePath, module);
+    mod.filename = filePath;
+    mod.paths = Module._nodeModulePaths(path.dirname(filePath));
+    mod._compile(code, filePath);
+    return mod.exports;
+}
+
+// Ratcliff/Obershelp similarity, same as Python's difflib.SequenceMatcher.ratio()
+function ratio(a, b) {
+    const matched = (a, b) => {
+        let best = 0, ai = 0, bi = 0;
+        for (let i = 0; i < a.length; i++) {
+            for (let j = 0; j < b.length; j++) {
+                let k = 0;
+                while (i + k < a.length && j + k < b.length && a[i + k] === b[j + k]) k++;
+                if (k > best) { best = k; ai = i; bi = j; }
+            }
+        }
+        if (!best) return 0;
+        return best + matched(a.slice(0, ai), b.slice(0, bi))
+            + matched(a.slice(ai + best), b.slice(bi + best));
+    };
+    return a.length + b.length ? (2 * matched(a, b)) / (a.length + b.length) : 1;
+}
+
+// Find function by exact name or best fuzzy match (name + arity similarity).
+// Looks at exports first, then top-level declarations reached through the scope hook.
+function fuzzyLookup(exported, declared, name, argc) {
+    const scope = globalThis.__scriptmark_scope__;
+    const get = (n) => {
+        if (exported != null && typeof exported[n] === "function") return exported[n];
+        if (typeof exported === "function" && exported.name === n) return exported;
+        try {
+            const v = scope && scope(n);
+            if (typeof v === "function") return v;
+        } catch {}
+        return undefined;
+    };
+    const exact = get(name);
+    if (exact) return [exact, name];
+
+    const names = new Set(declared);
+    if (exported != null && (typeof exported === "object" || typeof exported === "function")) {
+        Object.keys(exported).forEach((n) => names.add(n));
+    }
+    if (typeof exported === "function" && exported.name) names.add(exported.name);
+
+    let best = null;
+    for (const n of names) {
+        if (n.startsWith("_")) continue;
+        const fn = get(n);
+        if (!fn) continue;
+        const score = ratio(name.toLowerCase(), n.toLowerCase()) + (fn.length === argc ? 0.2 : 0);
+        if (!best || score > best[2]) best = [fn, n, score];
+    }
+    if (best && best[2] >= 0.5) return [best[0], best[1]];
+    return [undefined, name];
+}
+
+function typeName(v) {
+    if (v === null) return "null";
+    if (Array.isArray(v)) return "Array";
+    if (typeof v === "object") return (v.constructor && v.constructor.name) || "Object";
+    return typeof v;
+}
+
+function toJson(v) {
+    return JSON.stringify(v, (_k, x) => {
+        if (x instanceof Map) return Object.fromEntries(x);
+        if (x instanceof Set) return [...x];
+        if (typeof x === "bigint") return Number(x);
+        return x === undefined ? null : x;
+    });
+}
+
+(async () => {
+    let code;
+    try {
+        code = fs.readFileSync(filePath, "utf8");
+    } catch (e) {
+        fail("ImportError", `Cannot load ${filePath}`);
+    }
+    if (code.startsWith("#!")) code = "//" + code;
+    if (/\.[cm]?ts$/.test(filePath)) code = stripTypes(code);
+
+    if (payload.mode === "run") {
+        // IO mode: run the (transpiled) program as-is, with argv as if run directly
+        process.argv[1] = filePath;
+        await load(code);
+        return;
+    }
+
+    const declared = [];
+    const decl = /^(?:export\s+(?:default\s+)?)?(?:async\s+)?(?:function\s*\*?\s*|(?:const|let|var|class)\s+)([A-Za-z_$][\w$]*)/gm;
+    for (const m of code.matchAll(decl)) declared.push(m[1]);
+    code += "\n;globalThis.__scriptmark_scope__ = (n) => eval(n);\n";
+
+    // Inject vars as globals (teacher-defined constants)
+    for (const [key, val] of Object.entries(payload.vars || {})) globalThis[key] = val;
+
+    // Swallow prints during module load so stdout stays a single envelope
+    process.stdout.write = () => true;
+
+    let exported;
+    try {
+        exported = await load(code);
+    } catch (e) {
+        fail(errorName(e), e && e.message);
+    }
+
+    const args = payload.args;
+    const [fn, matchedName] = fuzzyLookup(exported, declared, payload.function, args.length);
+    if (!fn) fail("AttributeError", `Function '${payload.function}' not found`);
+    if (matchedName !== payload.function) fuzzy = { matched_name: matchedName };
+    // Capture what the call prints; it is reported next to the return value
+    process.stdout.write = (chunk) => {
+        printed += typeof chunk === "string" ? chunk : Buffer.from(chunk).toString();
+        return true;
+    };
+    try {
+        let result = fn(...args);
+        if (result && typeof result.then === "function") result = await result;
+        emit({ ok: true, value: JSON.parse(toJson(result) ?? "null"), type: typeName(result), stdout: printed, ...fuzzy });
+    } catch (e) {
+        fail(errorName(e), e instanceof Error ? e.message : String(e));
+    }
+})();
+"##;
+
+/// Executor for JavaScript and TypeScript, run with Node.
+pub struct NodeExecutor {
+	language: String,
+	node_cmd: String,
+	sandbox: SandboxConfig,
+	python_cmd: String,
+}
+
+impl NodeExecutor {
+	fn new(language: &str) -> Self {
+		Self {
+			language: language.to_string(),
+			node_cmd: resolve_program_path("node"),
+			// V8 reserves ~1 GB of address space up front and runs helper threads
+			sandbox: SandboxConfig {
+				mem_mb: 2048,
+				nproc: 512,
+				..SandboxConfig::default()
+			},
+			python_cmd: resolve_program_path("python3"),
+		}
+	}
+
+	/// Executor for `.js` files.
+	pub fn javascript() -> Self {
+		Self::new("javascript")
+	}
+
+	/// Executor for `.ts` files.
+	pub fn typescript() -> Self {
+		Self::new("typescript")
+	}
+
+	pub fn with_node_cmd(mut self, node_cmd: impl Into<String>) -> Self {
+		self.node_cmd = resolve_program_path(&node_cmd.into());
+		self
+	}
+
+	/// Python interpreter for Python verifier checks. Default: `python3`.
+	pub fn with_python_cmd(mut self, python_cmd: impl Into<String>) -> Self {
+		self.python_cmd = resolve_program_path(&python_cmd.into());
+		self
+	}
+
+	/// Execute a function-call test case via the helper script.
+	async fn execute_function_call(
+		&self,
+		student_file: &StudentFile,
+		function_name: &str,
+		case: &TestCase,
+		spec: &TestSpec,
+		timeout_secs: u64,
+	) -> CaseResult {
+		if !case.kwargs.is_empty() {
+			return setup_error_result(
+				case,
+				format!("Keyword arguments are not supported for {}", self.language),
+			);
+		}
+		let start = Instant::now();
+
+		let payload = serde_json::json!({
+			"file": absolute_path(&student_file.path).to_string_lossy(),
+			"function": function_name,
+			"args": case.args,
+			"vars": spec.vars,
+			"allowed_imports": spec.meta.allowed_imports,
+		});
+
+		let case_dir = match CaseDir::new(case) {
+			Ok(dir) => dir,
+			Err(e) => return setup_error_result(case, e),
+		};
+		let mut cmd = sandboxed_command(&self.node_cmd, &self.sandbox);
+		cmd.arg("-e").arg(HELPER_SCRIPT).arg(payload.to_string());
+		case_dir.apply_function(&mut cmd, case);
+
+		let stdin = inputs_as_stdin(case);
+		let result = spawn_with_timeout(cmd, stdin.as_deref(), timeout_secs).await;
+		let elapsed = start.elapsed().as_millis() as u64;
+
+		match result {
+			Err(SpawnError::Timeout) => CaseResult {
+				case_name: case.name.clone(),
+				status: TestStatus::Timeout,
+				actual: None,
+				expected: case.expect.as_ref().map(|v| v.to_string()),
+				failure: Some(FailureDetail {
+					message: format!("Timed out after {timeout_secs}s"),
+					details: String::new(),
+				}),
+				elapsed_ms: Some(elapsed),
+				matched_name: None,
+			},
+			Err(SpawnError::Spawn(e)) => CaseResult {
+				case_name: case.name.clone(),
+				status: TestStatus::Error,
+				actual: None,
+				expected: None,
+				failure: Some(FailureDetail {
+					message: format!("Failed to spawn node: {e}"),
+					details: String::new(),
+				}),
+				elapsed_ms: Some(elapsed),
+				matched_name: None,
+			},
+			Ok(output) => {
+				let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+				let result = evaluate_function_result(&stdout, case, elapsed, &self.python_cmd);
+				check_expected_files(result, case, case_dir.path(), &self.python_cmd)
+			}
+		}
+	}
+
+	/// Execute an IO-based test case (stdin → stdout).
+	async fn execute_io_test(
+		&self,
+		student_file: &StudentFile,
+		case: &TestCase,
+		timeout_secs: u64,
+	) -> CaseResult {
+		let start = Instant::now();
+
+		let path = absolute_path(&student_file.path);
+		let mut cmd = sandboxed_command(&self.node_cmd, &self.sandbox);
+		if self.language == "typescript" {
+			// Node can't run .ts directly everywhere; let the helper strip types first
+			let payload = serde_json::json!({
+				"file": path.to_string_lossy(),
+				"mode": "run",
+			});
+			cmd.arg("-e").arg(HELPER_SCRIPT).arg(payload.to_string());
+		} else {
+			cmd.arg(&path);
+		}
+		let case_dir = match CaseDir::new(case) {
+			Ok(dir) => dir,
+			Err(e) => return setup_error_result(case, e),
+		};
+		case_dir.apply_io(&mut cmd, case);
+
+		let result = spawn_with_timeout(cmd, case.stdin.as_deref(), timeout_secs).await;
+		let result = evaluate_io_output(result, case, timeout_secs, start, &self.python_cmd);
+		check_expected_files(result, case, case_dir.path(), &self.python_cmd)
+	}
+
+	async fn run_case(
+		&self,
+		student_files: &[StudentFile],
+		spec: &TestSpec,
+		case: &TestCase,
+		timeout_secs: u64,
+	) -> CaseResult {
+		let function = case.function.as_ref().or(spec.meta.function.as_ref());
+		let Some(student_file) =
+			find_student_file(student_files, &spec.meta.file, function.map(|f| f.as_str()))
+		else {
+			return missing_file_result(case, &spec.meta.file);
+		};
+
+		match function {
+			Some(function_name) => {
+				self.execute_function_call(student_file, function_name, case, spec, timeout_secs)
+					.await
+			}
+			None => self.execute_io_test(student_file, case, timeout_secs).await,
+		}
+	}
+}
+
+impl Executor for NodeExecutor {
+	fn language(&self) -> &str {
+		&self.language
+	}
+
+	fn python_cmd(&self) -> &str {
+		&self.python_cmd
+	}
+
+	fn execute_case<'a>(
+		&'a self,
+		student_files: &'a [StudentFile],
+		spec: &'a TestSpec,
+		case: &'a TestCase,
+		timeout_secs: u64,
+	) -> BoxFuture<'a, CaseResult> {
+		Box::pin(self.run_case(student_files, spec, case, timeout_secs))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::Path;
+
+	fn spec(toml_str: &str) -> TestSpec {
+		toml::from_str(toml_str).unwrap()
+	}
+
+	fn js_file(dir: &Path, name: &str, code: &str) -> Vec<StudentFile> {
+		let path = dir.join(name);
+		std::fs::write(&path, code).unwrap();
+		vec![StudentFile {
+			path,
+			language: "javascript".to_string(),
+		}]
+	}
+
+	#[tokio::test]
+	async fn test_function_cases_with_fuzzy_lookup() {
+		let dir = tempfile::tempdir().unwrap();
+		// Nothing exported: top-level declarations are still reachable
+		let files = js_file(
+			dir.path(),
+			"alice_lab1.js",
+			"console.log('loading');\nfunction addNums(a, b) { return a + b; }\nconst uniq = (xs) => new Set(xs);\nfunction boom() { throw new RangeError('too big'); }\nfunction greet(n) { console.log(`hi ${n}`); }\nconst readNums = () => require('fs').readFileSync(0, 'utf8').split('\\n').filter(Boolean).map(Number);\n",
+		);
+		let spec = spec(
+			r#"
+[meta]
+name = "lab1"
+file = "lab1.js"
+language = "javascript"
+
+[[cases]]
+name = "fuzzy name"
+function = "add_nums"
+args = [2, 3]
+expect = 5
+
+[[cases]]
+name = "set"
+function = "uniq"
+args = [[1, 1, 2]]
+expect = [1, 2]
+
+[[cases]]
+name = "throws"
+function = "boom"
+expect_error = "RangeError"
+
+[[cases]]
+name = "prints"
+function = "greet"
+args = [3]
+expected_stdout = "hi 3\n"
+
+[[cases]]
+name = "reads inputs from stdin"
+function = "readNums"
+inputs = ["4", "5"]
+expect = [4, 5]
+"#,
+		);
+		let executor = NodeExecutor::javascript();
+		let results = executor.execute_chain(&files, &spec, &spec.cases, 10).await;
+		for r in &results {
+			assert_eq!(r.status, TestStatus::Passed, "{r:?}");
+		}
+		assert_eq!(results[0].matched_name.as_deref(), Some("addNums"));
+		assert_eq!(results[1].matched_name, None);
+	}
+
+	#[tokio::test]
+	async fn test_esm_exports_and_import_guard() {
+		let dir = tempfile::tempdir().unwrap();
+		let mut files = js_file(
+			dir.path(),
+			"greet.mjs",
+			"export async function greet(n) { return `hi ${n}`; }\n",
+		);
+		files.extend(js_file(
+			dir.path(),
+			"shell.js",
+			"const cp = require('child_process');\nfunction run() { return 1; }\n",
+		));
+		let spec = spec(
+			r#"
+[meta]
+name = "esm"
+file = "greet.mjs"
+language = "javascript"
+function = "greet"
+
+[[cases]]
+name = "greet"
+args = ["bob"]
+expect = "hi bob"
+"#,
+		);
+		let executor = NodeExecutor::javascript();
+		let r = executor
+			.execute_case(&files, &spec, &spec.cases[0], 10)
+			.await;
+		assert_eq!(r.status, TestStatus::Passed, "{r:?}");
+
+		let mut guarded = spec.clone();
+		guarded.meta.file = "shell.js".to_string();
+		guarded.meta.function = Some("run".to_string());
+		let r = executor
+			.execute_case(&files, &guarded, &guarded.cases[0], 10)
+			.await;
+		assert!(r.actual.unwrap().starts_with("ImportError"));
+	}
+
+	#[tokio::test]
+	async fn test_io_case_runs_script() {
+		let dir = tempfile::tempdir().unwrap();
+		let files = js_file(
+			dir.path(),
+			"double.js",
+			"const n = Number(require('fs').readFileSync(0, 'utf8'));\nconsole.log(n * 2);\n",
+		);
+		let spec = spec(
+			r#"
+[meta]
+name = "double"
+file = "double.js"
+language = "javascript"
+
+[[cases]]
+name = "21"
+stdin = "21\n"
+expected_stdout = "42\n"
+"#,
+		);
+		let r = NodeExecutor::javascript()
+			.execute_case(&files, &spec, &spec.cases[0], 10)
+			.await;
+		assert_eq!(r.status, TestStatus::Passed, "{r:?}");
+	}
+}
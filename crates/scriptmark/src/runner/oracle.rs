@@ -1,23 +1,245 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use serde::{Deserialize, Serialize};
+
+use crate::checker::rhai_checker::{dynamic_to_json, json_to_dynamic};
 use crate::models::spec::Oracle;
-use crate::models::{StudentFile, TestCase, TestSpec};
+use crate::models::{CaseResult, FailureDetail, StudentFile, TestCase, TestSpec, TestStatus};
+
+use crate::runner::executor::Executor;
+use crate::runner::expander::{arg_order, case_name, expand_cases};
+use crate::runner::process::{SpawnError, spawn_with_timeout};
+
+/// Seconds the reference solution may spend on each generated case.
+const REFERENCE_TIMEOUT_SECS: u64 = 10;
+
+/// Expanded cases of a spec, with oracle expectations filled in.
+pub struct PreparedCases {
+	/// Cases ready to run against every student.
+	pub cases: Vec<TestCase>,
+	/// Error results for generated cases that could not be generated or
+	/// whose oracle failed, each with its position among the spec's results.
+	pub errors: Vec<(usize, CaseResult)>,
+}
+
+impl PreparedCases {
+	/// Merge the results of running `cases` with the error results, keeping
+	/// every case at its position in the spec.
+	pub fn merge(&self, mut results: Vec<CaseResult>) -> Vec<CaseResult> {
+		for (index, error) in &self.errors {
+			results.insert((*index).min(results.len()), error.clone());
+		}
+		results
+	}
+}
+
+/// Expand a spec's parametrized cases and resolve their oracles.
+///
+/// Runs once per spec, before cases fan out to students. A reference oracle
+/// runs all of its generated cases in one batch and caches the results in an
+/// expectations file next to the reference solution.
+pub async fn prepare_cases(spec: &TestSpec, executor: &dyn Executor) -> PreparedCases {
+	let mut cases = Vec::new();
+	let mut errors = Vec::new();
+	for case in &spec.cases {
+		let Some(param) = &case.parametrize else {
+			cases.push(case.clone());
+			continue;
+		};
+		let expanded = match expand_cases(std::slice::from_ref(case)) {
+			Ok(expanded) => expanded,
+			Err(e) => {
+				for i in 0..param.count {
+					let error =
+						error_case(case_name(&case.name, i), "Generator failed", e.to_string());
+					errors.push((cases.len() + errors.len(), error));
+				}
+				continue;
+			}
+		};
+
+		if let Some(ref_path) = &param.oracle.reference {
+			let seed = param.seed.unwrap_or(0);
+			let values = reference_expectations(ref_path, seed, spec, executor, &expanded).await;
+			for (mut generated, value) in expanded.into_iter().zip(values) {
+				match value {
+					Ok(value) => {
+						generated.expect = Some(value);
+						cases.push(generated);
+					}
+					Err(message) => errors.push((
+						cases.len() + errors.len(),
+						oracle_error(&generated, message),
+					)),
+				}
+			}
+			continue;
+		}
+
+		// Expansion succeeded, so the argument order is valid
+		let arg_names = arg_order(param).unwrap_or_default();
+		for mut generated in expanded {
+			match resolve_oracle(
+				&mut generated,
+				&param.oracle,
+				&arg_names,
+				executor.python_cmd(),
+			)
+			.await
+			{
+				Ok(()) => cases.push(generated),
+				Err(message) => errors.push((
+					cases.len() + errors.len(),
+					oracle_error(&generated, message),
+				)),
+			}
+		}
+	}
+	PreparedCases { cases, errors }
+}
 
-use crate::runner::python::PythonExecutor;
+/// Error result for a generated case whose oracle could not produce an
+/// expected value.
+fn oracle_error(case: &TestCase, message: String) -> CaseResult {
+	error_case(case.name.clone(), "Oracle failed", message)
+}
+
+/// Error result headed by the first line of `message`, which is kept whole
+/// in the details.
+fn error_case(case_name: String, what: &str, message: String) -> CaseResult {
+	CaseResult {
+		case_name,
+		status: TestStatus::Error,
+		actual: None,
+		expected: None,
+		failure: Some(FailureDetail {
+			message: format!("{what}: {}", message.lines().next().unwrap_or_default()),
+			details: message,
+		}),
+		elapsed_ms: Some(0),
+		matched_name: None,
+	}
+}
 
-/// Resolve the expected value for a parametrized case using the oracle.
-pub async fn resolve_oracle(
+/// Resolve the expected value for a generated case from a Rhai, check or
+/// Python oracle. Python oracles run under `python_cmd`.
+///
+/// An `Err` carries the oracle's diagnostics; the case must not run without
+/// its expectation.
+async fn resolve_oracle(
 	case: &mut TestCase,
 	oracle: &Oracle,
-	spec: &TestSpec,
-	executor: &PythonExecutor,
 	arg_names: &[String],
-) {
-	if let Some(ref_path) = &oracle.reference {
-		// Run teacher's reference implementation with same function + args
+	python_cmd: &str,
+) -> Result<(), String> {
+	if let Some(rhai_expr) = &oracle.rhai {
+		// Evaluate Rhai expression with arg names as variables
+		let engine = rhai::Engine::new();
+		let mut scope = rhai::Scope::new();
+		for (i, name) in arg_names.iter().enumerate() {
+			if let Some(val) = case.args.get(i) {
+				scope.push_dynamic(name.as_str(), json_to_dynamic(val));
+			}
+		}
+		let result = engine
+			.eval_with_scope::<rhai::Dynamic>(&mut scope, rhai_expr)
+			.map_err(|e| format!("Rhai oracle '{rhai_expr}' failed: {e}"))?;
+		case.expect =
+			Some(dynamic_to_json(&result).map_err(|e| {
+				format!("Rhai oracle '{rhai_expr}' returned an unusable value: {e}")
+			})?);
+	} else if let Some(check_name) = &oracle.check {
+		// Just set the checker — no expected value needed
+		case.check = Some(crate::models::CheckMethod::Builtin(check_name.clone()));
+	} else if let Some(script) = &oracle.python {
+		let timeout_secs = oracle.timeout.unwrap_or(10);
+		case.expect =
+			Some(run_python_oracle(python_cmd, script, case, arg_names, timeout_secs).await?);
+	}
+	Ok(())
+}
+
+/// Run a teacher's Python oracle script on the case's generated args.
+///
+/// Protocol:
+/// - stdin:  JSON `{"args": [...], "named": {"<arg name>": ..., ...}}`
+/// - stdout: the expected value as JSON
+async fn run_python_oracle(
+	python_cmd: &str,
+	script: &str,
+	case: &TestCase,
+	arg_names: &[String],
+	timeout_secs: u64,
+) -> Result<serde_json::Value, String> {
+	let named: serde_json::Map<String, serde_json::Value> = arg_names
+		.iter()
+		.cloned()
+		.zip(case.args.iter().cloned())
+		.collect();
+	let input = serde_json::json!({ "args": case.args, "named": named }).to_string();
+
+	let mut cmd = tokio::process::Command::new(python_cmd);
+	cmd.arg(script)
+		.stdout(std::process::Stdio::piped())
+		.stderr(std::process::Stdio::piped());
+	let label = format!("Python oracle '{script}'");
+	let output = match spawn_with_timeout(cmd, Some(&input), timeout_secs).await {
+		Ok(output) => output,
+		Err(SpawnError::Timeout) => return Err(format!("{label} timed out after {timeout_secs}s")),
+		Err(SpawnError::Spawn(e)) => return Err(format!("Failed to spawn {label}: {e}")),
+	};
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	if !output.status.success() {
+		return Err(format!(
+			"{label} exited with {}: {}",
+			output.status,
+			stderr.trim()
+		));
+	}
+	serde_json::from_str(stdout.trim()).map_err(|e| {
+		format!(
+			"{label} printed invalid JSON: {e}\nRaw output: {}",
+			stdout.trim()
+		)
+	})
+}
+
+/// Expected values for generated cases from the teacher's reference solution.
+///
+/// Cases already in the expectations file (same reference source, function,
+/// seed and args) are reused; the rest run in a single `execute_chain` call
+/// and are added to the file.
+async fn reference_expectations(
+	ref_path: &str,
+	seed: u64,
+	spec: &TestSpec,
+	executor: &dyn Executor,
+	cases: &[TestCase],
+) -> Vec<Result<serde_json::Value, String>> {
+	let reference = Path::new(ref_path);
+	let function = spec.meta.function.clone().unwrap_or_default();
+	let mut file = ExpectationsFile::load(reference);
+	let mut values: Vec<Option<Result<serde_json::Value, String>>> = cases
+		.iter()
+		.map(|c| file.lookup(&function, seed, &c.args).cloned().map(Ok))
+		.collect();
+
+	let missing: Vec<TestCase> = cases
+		.iter()
+		.zip(&values)
+		.filter(|(_, value)| value.is_none())
+		.map(|(c, _)| TestCase {
+			expect: None,
+			check: None,
+			timeout: Some(REFERENCE_TIMEOUT_SECS),
+			..c.clone()
+		})
+		.collect();
+	if !missing.is_empty() {
 		let ref_file = StudentFile {
-			path: Path::new(ref_path).to_path_buf(),
-			language: "python".to_string(),
+			path: reference.to_path_buf(),
+			language: spec.meta.language.clone(),
 		};
 		let ref_spec = TestSpec {
 			meta: spec.meta.clone(),
@@ -25,45 +247,158 @@ pub async fn resolve_oracle(
 			setup: vec![],
 			cases: vec![],
 			lint: None,
+			requirements: None,
 		};
-		let result = executor
-			.execute_case(&[ref_file], &ref_spec, case, 10)
+		let results = executor
+			.execute_chain(
+				&[ref_file],
+				&ref_spec,
+				&missing,
+				REFERENCE_TIMEOUT_SECS * missing.len() as u64,
+			)
 			.await;
-		if let Some(actual) = &result.actual {
-			case.expect = serde_json::from_str(actual).ok();
-		}
-	} else if let Some(rhai_expr) = &oracle.rhai {
-		// Evaluate Rhai expression with arg names as variables
-		let engine = rhai::Engine::new();
-		let mut scope = rhai::Scope::new();
-		for (i, name) in arg_names.iter().enumerate() {
-			if let Some(val) = case.args.get(i) {
-				scope.push_dynamic(
-					name.as_str(),
-					crate::checker::rhai_checker::json_to_dynamic(val),
-				);
+
+		let mut results = results.iter();
+		let mut added = false;
+		for (case, value) in cases.iter().zip(values.iter_mut()) {
+			if value.is_some() {
+				continue;
 			}
+			let result = match results.next() {
+				Some(result) => reference_value(ref_path, result),
+				None => Err(format!("Reference oracle '{ref_path}' returned no result")),
+			};
+			if let Ok(expect) = &result {
+				file.insert(&case.name, &function, seed, &case.args, expect.clone());
+				added = true;
+			}
+			*value = Some(result);
 		}
-		if let Ok(result) = engine.eval_with_scope::<rhai::Dynamic>(&mut scope, rhai_expr) {
-			case.expect = Some(dynamic_to_json(&result));
+		if added && let Err(e) = file.save(reference) {
+			eprintln!(
+				"Failed to write expectations file '{}': {e}",
+				ExpectationsFile::path(reference).display()
+			);
 		}
-	} else if let Some(check_name) = &oracle.check {
-		// Just set the checker — no expected value needed
-		case.check = Some(crate::models::CheckMethod::Builtin(check_name.clone()));
 	}
-	// oracle.python — TODO for future
+	values.into_iter().flatten().collect()
 }
 
-fn dynamic_to_json(val: &rhai::Dynamic) -> serde_json::Value {
-	if let Ok(b) = val.as_bool() {
-		serde_json::Value::from(b)
-	} else if let Ok(i) = val.as_int() {
-		serde_json::Value::from(i)
-	} else if let Ok(f) = val.as_float() {
-		serde_json::Value::from(f)
-	} else if let Ok(s) = val.clone().into_string() {
-		serde_json::Value::from(s)
-	} else {
-		serde_json::Value::Null
+/// Expected values computed by a reference solution, kept next to it as
+/// `<stem>.expectations.json` so they can be inspected and reused.
+///
+/// Entries are only trusted while the reference source is unchanged: a file
+/// whose `source_hash` differs is discarded on load. The hash is tagged with
+/// its algorithm (`fnv1a64:<hex>`) so files stay valid across releases.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExpectationsFile {
+	source_hash: String,
+	expectations: Vec<Expectation>,
+}
+
+/// One cached expected value.
+#[derive(Debug, Serialize, Deserialize)]
+struct Expectation {
+	case: String,
+	function: String,
+	seed: u64,
+	args: Vec<serde_json::Value>,
+	expect: serde_json::Value,
+}
+
+impl ExpectationsFile {
+	fn path(reference: &Path) -> PathBuf {
+		reference.with_extension("expectations.json")
+	}
+
+	fn load(reference: &Path) -> Self {
+		let source_hash = std::fs::read(reference)
+			.map(|source| format!("fnv1a64:{:016x}", fnv1a64(&source)))
+			.unwrap_or_default();
+		std::fs::read_to_string(Self::path(reference))
+			.ok()
+			.and_then(|s| serde_json::from_str::<Self>(&s).ok())
+			.filter(|file| file.source_hash == source_hash)
+			.unwrap_or(Self {
+				source_hash,
+				expectations: Vec::new(),
+			})
+	}
+
+	fn lookup(
+		&self,
+		function: &str,
+		seed: u64,
+		args: &[serde_json::Value],
+	) -> Option<&serde_json::Value> {
+		self.expectations
+			.iter()
+			.find(|e| e.function == function && e.seed == seed && e.args == args)
+			.map(|e| &e.expect)
+	}
+
+	fn insert(
+		&mut self,
+		case: &str,
+		function: &str,
+		seed: u64,
+		args: &[serde_json::Value],
+		expect: serde_json::Value,
+	) {
+		self.expectations.push(Expectation {
+			case: case.to_string(),
+			function: function.to_string(),
+			seed,
+			args: args.to_vec(),
+			expect,
+		});
+	}
+
+	fn save(&self, reference: &Path) -> std::io::Result<()> {
+		let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+		std::fs::write(Self::path(reference), json + "\n")
+	}
+}
+
+/// 64-bit FNV-1a hash of `bytes`: fixed, unlike the std hasher, so stored
+/// hashes stay comparable.
+fn fnv1a64(bytes: &[u8]) -> u64 {
+	bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+		(hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+	})
+}
+
+/// The value the reference implementation returned for a case.
+///
+/// The reference runs with no expectation, so any status other than a
+/// checked result (passed or failed) means it crashed, timed out, or was not
+/// found. A reference that raised comes back as a failed result whose value
+/// is the exception itself.
+fn reference_value(ref_path: &str, result: &CaseResult) -> Result<serde_json::Value, String> {
+	let label = format!("Reference oracle '{ref_path}'");
+	if !matches!(result.status, TestStatus::Passed | TestStatus::Failed) {
+		let (message, details) = result
+			.failure
+			.as_ref()
+			.map(|f| (f.message.as_str(), f.details.as_str()))
+			.unwrap_or(("no diagnostics", ""));
+		return Err(
+			format!("{label} failed ({:?}): {message}\n{details}", result.status)
+				.trim_end()
+				.to_string(),
+		);
+	}
+	let actual = result
+		.actual
+		.as_deref()
+		.ok_or_else(|| format!("{label} returned no value"))?;
+	if let Some(failure) = &result.failure
+		&& failure.message == actual
+	{
+		return Err(format!("{label} raised: {actual}\n{}", failure.details)
+			.trim_end()
+			.to_string());
 	}
+	serde_json::from_str(actual)
+		.map_err(|e| format!("{label} returned a value that is not JSON: {e}\nRaw value: {actual}"))
 }
@@ -7,16 +7,20 @@ use crate::models::{
 };
 use tokio::sync::Semaphore;
 
-use crate::runner::python::PythonExecutor;
-use crate::runner::resolve::resolve_args;
+use crate::runner::executor::{Executor, ExecutorRegistry};
+use crate::runner::expander::case_name;
+use crate::runner::oracle::{PreparedCases, prepare_cases};
+use crate::runner::resolve::{resolve_args, resolve_refs};
 
 /// Run all test specs for all students in parallel.
 ///
+/// Each spec is dispatched to the executor registered for `spec.meta.language`.
 /// Concurrency is bounded by `max_concurrent` (defaults to number of CPUs).
+/// Parametrized cases and their oracles are resolved once per spec up front.
 pub async fn run_all(
 	submissions: &SubmissionSet,
 	specs: &[TestSpec],
-	executor: &PythonExecutor,
+	executors: &ExecutorRegistry,
 	timeout_secs: u64,
 	max_concurrent: Option<usize>,
 ) -> HashMap<String, StudentReport> {
@@ -27,20 +31,29 @@ pub async fn run_all(
 	});
 	let semaphore = Arc::new(Semaphore::new(concurrency));
 
+	let mut prepared = Vec::with_capacity(specs.len());
+	for spec in specs {
+		prepared.push(match executors.get(&spec.meta.language) {
+			Some(executor) => Some(prepare_cases(spec, executor.as_ref()).await),
+			None => None,
+		});
+	}
+	let prepared = Arc::new(prepared);
+
 	let mut handles = Vec::new();
 
 	for (sid, files) in &submissions.by_student {
 		let sid = sid.clone();
 		let files = files.clone();
 		let specs = specs.to_vec();
+		let prepared = prepared.clone();
 		let sem = semaphore.clone();
-		let python_cmd = executor.python_cmd().to_string();
+		let executors = executors.clone();
 		let timeout = timeout_secs;
 
 		let handle = tokio::spawn(async move {
 			let _permit = sem.acquire().await.unwrap();
-			let exec = PythonExecutor::with_python_cmd(&python_cmd);
-			let report = run_student(&exec, &sid, &files, &specs, timeout).await;
+			let report = run_student(&executors, &sid, &files, &specs, &prepared, timeout).await;
 			(sid, report)
 		});
 
@@ -59,15 +72,35 @@ pub async fn run_all(
 
 /// Run all test specs for a single student.
 async fn run_student(
-	executor: &PythonExecutor,
+	executors: &ExecutorRegistry,
 	sid: &str,
 	files: &[StudentFile],
 	specs: &[TestSpec],
+	prepared: &[Option<PreparedCases>],
 	timeout_secs: u64,
 ) -> StudentReport {
 	let mut test_results = Vec::new();
+	let mut backends: Vec<String> = Vec::new();
+
+	for (spec, prepared) in specs.iter().zip(prepared) {
+		let (Some(executor), Some(prepared)) = (executors.get(&spec.meta.language), prepared)
+		else {
+			test_results.push(unsupported_language(spec));
+			continue;
+		};
+		let executor: &dyn Executor = executor.as_ref();
+		if !backends.iter().any(|b| b == executor.language()) {
+			backends.push(executor.language().to_string());
+		}
+
+		// Only hand the executor files written in its language
+		let files: Vec<StudentFile> = files
+			.iter()
+			.filter(|f| f.language == spec.meta.language)
+			.cloned()
+			.collect();
+		let files = files.as_slice();
 
-	for spec in specs {
 		// 1. Seed context with vars
 		let mut context: HashMap<String, serde_json::Value> = HashMap::new();
 		for (key, value) in &spec.vars {
@@ -88,14 +121,7 @@ async fn run_student(
 					name: format!("setup:{}", step.id),
 					id: Some(step.id.clone()),
 					args: resolved_args,
-					expect: None,
-					expect_error: None,
-					stdin: None,
-					expected_stdout: None,
-					check: None,
-					timeout: None,
-					parametrize: None,
-					function: None,
+					..Default::default()
 				};
 
 				let setup_spec = TestSpec {
@@ -107,6 +133,7 @@ async fn run_student(
 					setup: vec![],
 					cases: vec![],
 					lint: None,
+					requirements: None,
 				};
 
 				let result = executor
@@ -153,47 +180,25 @@ async fn run_student(
 			context.insert(step.id.clone(), value);
 		}
 
-		// 3. Expand parametrized cases
-		let expanded_cases = crate::runner::expander::expand_cases(&spec.cases);
+		// 3. Parametrized cases were expanded and their oracles resolved in
+		// `run_all`; cases that failed to generate or resolve are reported as errors
+		let final_cases = &prepared.cases;
 
-		// 4. Resolve oracles for parametrized cases
-		let mut final_cases = Vec::new();
-		for mut case in expanded_cases {
-			// Find original parametrized case to get oracle config
-			let original = spec
-				.cases
+		// 4. Run cases — chain mode or per-case mode
+		let use_chain = !spec.meta.imports.is_empty()
+			|| final_cases
 				.iter()
-				.find(|c| case.name.starts_with(&c.name) && c.parametrize.is_some());
-			if let Some(orig) = original
-				&& let Some(param) = &orig.parametrize
-			{
-				let mut arg_names: Vec<String> = param.args.keys().cloned().collect();
-				arg_names.sort();
-				crate::runner::oracle::resolve_oracle(
-					&mut case,
-					&param.oracle,
-					spec,
-					executor,
-					&arg_names,
-				)
-				.await;
-			}
-			final_cases.push(case);
-		}
-
-		// 5. Run cases — chain mode or per-case mode
-		let use_chain =
-			!spec.meta.imports.is_empty() || final_cases.iter().any(|c| c.function.is_some());
+				.any(|c| c.function.is_some() || c.class.is_some());
 
-		let cases = if use_chain {
+		let mut cases = if use_chain {
 			// Chain mode: single subprocess handles setup + all cases
 			executor
-				.execute_chain(files, spec, &final_cases, timeout_secs)
+				.execute_chain(files, spec, final_cases, timeout_secs)
 				.await
 		} else {
 			// Per-case mode (original behavior)
 			let mut cases = Vec::new();
-			for case in &final_cases {
+			for case in final_cases {
 				if setup_failed {
 					cases.push(CaseResult {
 						case_name: case.name.clone(),
@@ -205,6 +210,7 @@ async fn run_student(
 							details: String::new(),
 						}),
 						elapsed_ms: Some(0),
+						matched_name: None,
 					});
 					continue;
 				}
@@ -214,6 +220,11 @@ async fn run_student(
 				// Resolve $ref in args
 				let resolved_case = crate::models::TestCase {
 					args: resolve_args(&case.args, &context),
+					kwargs: case
+						.kwargs
+						.iter()
+						.map(|(k, v)| (k.clone(), resolve_refs(v, &context)))
+						.collect(),
 					..case.clone()
 				};
 
@@ -224,6 +235,19 @@ async fn run_student(
 			}
 			cases
 		};
+		if spec.meta.strict_names {
+			reject_fuzzy_matches(&mut cases);
+		}
+		let mut cases = prepared.merge(cases);
+		cases.extend(
+			crate::runner::requirements::check_requirements(
+				files,
+				spec,
+				executor.python_cmd(),
+				timeout_secs,
+			)
+			.await,
+		);
 
 		test_results.push(TestResult {
 			spec_name: spec.meta.name.clone(),
@@ -243,12 +267,61 @@ async fn run_student(
 		}
 	}
 
+	backends.sort();
 	StudentReport {
 		student_id: sid.to_string(),
 		student_name: None,
 		test_results,
 		final_grade: None,
-		backend_name: Some("python".to_string()),
+		backend_name: (!backends.is_empty()).then(|| backends.join(",")),
 		lint_score,
 	}
 }
+
+/// Fail every case that only ran because of a fuzzy name match.
+fn reject_fuzzy_matches(cases: &mut [CaseResult]) {
+	for case in cases.iter_mut() {
+		if let Some(name) = &case.matched_name {
+			case.status = TestStatus::Failed;
+			case.failure = Some(FailureDetail {
+				message: format!("called '{name}' by fuzzy name match, which strict_names forbids"),
+				details: case
+					.failure
+					.as_ref()
+					.map(|f| f.message.clone())
+					.unwrap_or_default(),
+			});
+		}
+	}
+}
+
+/// Result for a spec whose language has no registered executor.
+fn unsupported_language(spec: &TestSpec) -> TestResult {
+	let cases = spec
+		.cases
+		.iter()
+		.flat_map(|c| match &c.parametrize {
+			Some(param) => (0..param.count).map(|i| case_name(&c.name, i)).collect(),
+			None => vec![c.name.clone()],
+		})
+		.map(|case_name| CaseResult {
+			case_name,
+			status: TestStatus::Error,
+			actual: None,
+			expected: None,
+			failure: Some(FailureDetail {
+				message: format!(
+					"No executor registered for language '{}'",
+					spec.meta.language
+				),
+				details: String::new(),
+			}),
+			elapsed_ms: Some(0),
+			matched_name: None,
+		})
+		.collect();
+	TestResult {
+		spec_name: spec.meta.name.clone(),
+		cases,
+	}
+}
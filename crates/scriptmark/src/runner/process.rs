@@ -0,0 +1,384 @@
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+use std::process::Output;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio::process::Command;
+
+use crate::models::{CaseFile, CaseResult, FailureDetail, StudentFile, TestCase, TestStatus};
+use crate::runner::sandbox::SandboxConfig;
+#[cfg(unix)]
+use crate::runner::sandbox::apply_sandbox;
+
+/// `PATH` given to sandboxed processes after `env_clear()`.
+pub(crate) const SANDBOX_PATH: &str = "/usr/bin:/usr/local/bin:/opt/homebrew/bin";
+
+pub(crate) enum SpawnError {
+	Spawn(std::io::Error),
+	Timeout,
+}
+
+/// Resolve a command to an absolute path so env_clear() doesn't change
+/// which binary runs. Falls back to the original string if resolution fails.
+pub(crate) fn resolve_program_path(cmd: &str) -> String {
+	if cmd.starts_with('/') {
+		return cmd.to_string();
+	}
+	if let Ok(output) = std::process::Command::new("which").arg(cmd).output()
+		&& output.status.success()
+		&& let Ok(path) = String::from_utf8(output.stdout)
+	{
+		let path = path.trim();
+		if !path.is_empty() {
+			return path.to_string();
+		}
+	}
+	cmd.to_string()
+}
+
+async fn read_pipe<R: tokio::io::AsyncRead + Unpin>(pipe: Option<R>) -> Vec<u8> {
+	use tokio::io::AsyncReadExt;
+	match pipe {
+		Some(mut p) => {
+			let mut buf = Vec::new();
+			let _ = p.read_to_end(&mut buf).await;
+			buf
+		}
+		None => Vec::new(),
+	}
+}
+
+/// Build a sandboxed Command with env isolation and resource limits.
+pub(crate) fn sandboxed_command(program: impl AsRef<OsStr>, sandbox: &SandboxConfig) -> Command {
+	let mut cmd = Command::new(program);
+	cmd.env_clear()
+		.env("PATH", SANDBOX_PATH)
+		.env("HOME", "/tmp")
+		.stdout(std::process::Stdio::piped())
+		.stderr(std::process::Stdio::piped());
+	#[cfg(unix)]
+	apply_sandbox(&mut cmd, sandbox);
+	#[cfg(not(unix))]
+	let _ = sandbox;
+	cmd
+}
+
+/// Spawn a command with timeout, killing the child on timeout.
+///
+/// `stdin` is written to the child and then closed; without it the child
+/// gets `/dev/null` so a stray read never blocks on the grader's terminal.
+pub(crate) async fn spawn_with_timeout(
+	mut cmd: Command,
+	stdin: Option<&str>,
+	timeout_secs: u64,
+) -> Result<Output, SpawnError> {
+	cmd.stdin(if stdin.is_some() {
+		std::process::Stdio::piped()
+	} else {
+		std::process::Stdio::null()
+	});
+	let mut child = cmd.spawn().map_err(SpawnError::Spawn)?;
+
+	// Take pipes and read concurrently with wait to avoid deadlock
+	// (if child fills the pipe buffer, it blocks until someone reads)
+	let stdin_pipe = child.stdin.take();
+	let stdout_pipe = child.stdout.take();
+	let stderr_pipe = child.stderr.take();
+
+	let write_stdin = async move {
+		use tokio::io::AsyncWriteExt;
+		if let (Some(mut pipe), Some(data)) = (stdin_pipe, stdin) {
+			let _ = pipe.write_all(data.as_bytes()).await;
+			let _ = pipe.shutdown().await;
+		}
+	};
+
+	let timeout = std::time::Duration::from_secs(timeout_secs);
+	match tokio::time::timeout(timeout, async {
+		let (_, stdout, stderr, status) = tokio::join!(
+			write_stdin,
+			read_pipe(stdout_pipe),
+			read_pipe(stderr_pipe),
+			child.wait(),
+		);
+		status.map(|s| Output {
+			status: s,
+			stdout,
+			stderr,
+		})
+	})
+	.await
+	{
+		Ok(Ok(output)) => Ok(output),
+		Ok(Err(e)) => Err(SpawnError::Spawn(e)),
+		Err(_) => {
+			let _ = child.kill().await;
+			Err(SpawnError::Timeout)
+		}
+	}
+}
+
+static CASE_DIR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Fresh working directory for one IO case, removed when dropped.
+pub(crate) struct CaseDir {
+	path: PathBuf,
+}
+
+impl CaseDir {
+	/// Create the directory and write the case's `files` into it.
+	pub fn new(case: &TestCase) -> Result<Self, String> {
+		let dir = Self {
+			path: std::env::temp_dir().join(format!(
+				"scriptmark-case-{}-{}",
+				std::process::id(),
+				CASE_DIR_COUNTER.fetch_add(1, Ordering::Relaxed)
+			)),
+		};
+		std::fs::create_dir_all(&dir.path)
+			.map_err(|e| format!("Failed to create working dir: {e}"))?;
+
+		for (name, file) in &case.files {
+			let Some(rel) = case_relative_path(name) else {
+				return Err(format!(
+					"Case file '{name}' must be a relative path inside the working dir"
+				));
+			};
+			let dest = dir.path.join(rel);
+			if let Some(parent) = dest.parent() {
+				std::fs::create_dir_all(parent)
+					.map_err(|e| format!("Failed to create dir for '{name}': {e}"))?;
+			}
+			match file {
+				CaseFile::Inline(content) => std::fs::write(&dest, content)
+					.map_err(|e| format!("Failed to write '{name}': {e}"))?,
+				CaseFile::Copy { source } => {
+					std::fs::copy(source, &dest)
+						.map_err(|e| format!("Failed to copy '{source}' to '{name}': {e}"))?;
+				}
+			}
+		}
+
+		Ok(dir)
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Run `cmd` here as an IO program: adds the case's `argv` (so call this
+	/// after the program's own arguments) and `env`.
+	pub fn apply_io(&self, cmd: &mut Command, case: &TestCase) {
+		cmd.args(&case.argv).envs(&case.env).current_dir(&self.path);
+	}
+
+	/// Run a function-call helper here, with the case's `env`.
+	pub fn apply_function(&self, cmd: &mut Command, case: &TestCase) {
+		cmd.envs(&case.env).current_dir(&self.path);
+	}
+}
+
+/// `name` as a path inside a case's working dir, or `None` when it is
+/// absolute or climbs out with `..`.
+pub(crate) fn case_relative_path(name: &str) -> Option<&Path> {
+	let rel = Path::new(name);
+	rel.components()
+		.all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+		.then_some(rel)
+}
+
+impl Drop for CaseDir {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_dir_all(&self.path);
+	}
+}
+
+/// A function case's `inputs` as stdin lines, or `None` when it has none.
+pub(crate) fn inputs_as_stdin(case: &TestCase) -> Option<String> {
+	if case.inputs.is_empty() {
+		return None;
+	}
+	Some(case.inputs.iter().map(|line| format!("{line}\n")).collect())
+}
+
+/// `path` made absolute, for processes that run in a [`CaseDir`].
+pub(crate) fn absolute_path(path: &Path) -> PathBuf {
+	std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Error result for a case that could not be set up (working directory,
+/// unsupported options, ...).
+pub(crate) fn setup_error_result(case: &TestCase, message: String) -> CaseResult {
+	CaseResult {
+		case_name: case.name.clone(),
+		status: TestStatus::Error,
+		actual: None,
+		expected: None,
+		failure: Some(FailureDetail {
+			message,
+			details: String::new(),
+		}),
+		elapsed_ms: Some(0),
+		matched_name: None,
+	}
+}
+
+/// Find the student file matching the spec's file pattern.
+///
+/// Strategy (scored, best wins):
+/// 1. Exact suffix match (100) → highest confidence
+/// 2. Strip numeric prefixes, compare stems (80-100)
+/// 3. Stem-contains (40-60)
+/// 4. Definition scan (+200 bonus) → if `definition` is given (e.g.
+///    `def function_name`), files containing it get a large boost
+pub(crate) fn find_student_file<'a>(
+	student_files: &'a [StudentFile],
+	pattern: &str,
+	definition: Option<&str>,
+) -> Option<&'a StudentFile> {
+	let pattern_stem = Path::new(pattern)
+		.file_stem()
+		.and_then(|s| s.to_str())
+		.unwrap_or(pattern);
+
+	// 1. Exact suffix match — highest confidence
+	if let Some(f) = student_files.iter().find(|f| {
+		f.path
+			.file_name()
+			.and_then(|n| n.to_str())
+			.is_some_and(|n| n.ends_with(pattern))
+	}) {
+		return Some(f);
+	}
+
+	// Helper: strip numeric prefix segments (SID_uploadID_fileID_)
+	// "21300110043_171469_6012331_Lab3_2-2.py" → "Lab3_2-2.py"
+	fn extract_actual_name(filename: &str) -> &str {
+		let mut rest = filename;
+		loop {
+			if let Some(idx) = rest.find('_') {
+				let prefix = &rest[..idx];
+				if prefix.chars().all(|c| c.is_ascii_digit()) {
+					rest = &rest[idx + 1..];
+					continue;
+				}
+			}
+			break;
+		}
+		rest
+	}
+
+	// 2. Score all candidates by filename similarity + definition content
+	let mut scored: Vec<(&'a StudentFile, u32)> = student_files
+		.iter()
+		.filter_map(|f| {
+			let filename = f.path.file_name()?.to_str()?;
+			let actual = extract_actual_name(filename);
+			let actual_stem = Path::new(actual)
+				.file_stem()
+				.and_then(|s| s.to_str())
+				.unwrap_or(actual);
+
+			let mut score: u32 = 0;
+
+			// --- Filename similarity ---
+			if actual_stem == pattern_stem {
+				score += 100;
+			} else if actual_stem.starts_with(pattern_stem) {
+				score += 80;
+			} else if actual.contains(pattern_stem) {
+				score += 60;
+			} else if filename.contains(pattern_stem) {
+				score += 40;
+			}
+
+			// --- Definition scan (highest priority tiebreaker) ---
+			if let Some(needle) = definition
+				&& let Ok(content) = std::fs::read_to_string(&f.path)
+				&& content.contains(needle)
+			{
+				score += 200; // trumps filename-only matches
+			}
+
+			if score > 0 { Some((f, score)) } else { None }
+		})
+		.collect();
+
+	scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+	scored.first().map(|(f, _)| *f)
+}
+
+/// Error result used when no student file matches the spec.
+pub(crate) fn missing_file_result(case: &TestCase, pattern: &str) -> CaseResult {
+	CaseResult {
+		case_name: case.name.clone(),
+		status: TestStatus::Error,
+		actual: None,
+		expected: None,
+		failure: Some(FailureDetail {
+			message: format!("No file matching '{pattern}' found in submission"),
+			details: String::new(),
+		}),
+		elapsed_ms: Some(0),
+		matched_name: None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::path::PathBuf;
+
+	fn file(path: &str) -> StudentFile {
+		StudentFile {
+			path: PathBuf::from(path),
+			language: "python".to_string(),
+		}
+	}
+
+	#[test]
+	fn test_find_student_file_suffix_and_prefix() {
+		let files = vec![
+			file("/x/21300110043_171469_6012331_Lab3_2-2.py"),
+			file("/x/21300110043_171469_6012332_Lab3_1.py"),
+		];
+		let f = find_student_file(&files, "Lab3_1.py", None).unwrap();
+		assert!(f.path.ends_with("21300110043_171469_6012332_Lab3_1.py"));
+		let f = find_student_file(&files, "Lab3_2.py", None).unwrap();
+		assert!(f.path.ends_with("21300110043_171469_6012331_Lab3_2-2.py"));
+		assert!(find_student_file(&files, "hw9.py", None).is_none());
+	}
+
+	#[tokio::test]
+	async fn test_spawn_with_timeout_feeds_stdin() {
+		let mut cmd = sandboxed_command("cat", &SandboxConfig::default());
+		cmd.arg("-");
+		let output = match spawn_with_timeout(cmd, Some("hello\n"), 5).await {
+			Ok(o) => o,
+			Err(_) => panic!("cat failed"),
+		};
+		assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+	}
+
+	#[test]
+	fn test_case_dir_rejects_escaping_paths() {
+		let mut case = TestCase::default();
+		case.files.insert(
+			"../outside.txt".to_string(),
+			CaseFile::Inline("x".to_string()),
+		);
+		let err = CaseDir::new(&case).err().unwrap();
+		assert!(err.contains("must be a relative path"), "{err}");
+	}
+
+	#[tokio::test]
+	async fn test_spawn_with_timeout_kills_child() {
+		let mut cmd = sandboxed_command("sleep", &SandboxConfig::default());
+		cmd.arg("5");
+		assert!(matches!(
+			spawn_with_timeout(cmd, None, 1).await,
+			Err(SpawnError::Timeout)
+		));
+	}
+}
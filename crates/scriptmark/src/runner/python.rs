@@ -1,62 +1,75 @@
-use std::path::Path;
-use std::process::Output;
 use std::time::Instant;
 
-use crate::models::{CaseResult, FailureDetail, StudentFile, TestCase, TestSpec, TestStatus};
+use crate::models::{
+	CaseResult, CheckMethod, FailureDetail, MethodCall, StudentFile, TestCase, TestSpec, TestStatus,
+};
 use tokio::process::Command;
 
-use crate::checker::builtin::{ExactChecker, resolve_builtin};
-use crate::checker::{CheckInput, Checker};
-#[cfg(unix)]
-use crate::runner::sandbox::apply_sandbox;
-
-enum SpawnError {
-	Spawn(std::io::Error),
-	Timeout,
-}
-
-/// Resolve a python command to an absolute path so env_clear() doesn't change
-/// which interpreter runs. Falls back to the original string if resolution fails.
-fn resolve_python_path(cmd: &str) -> String {
-	if cmd.starts_with('/') {
-		return cmd.to_string();
-	}
-	if let Ok(output) = std::process::Command::new("which").arg(cmd).output()
-		&& output.status.success()
-		&& let Ok(path) = String::from_utf8(output.stdout)
-	{
-		let path = path.trim();
-		if !path.is_empty() {
-			return path.to_string();
-		}
-	}
-	cmd.to_string()
-}
-
-async fn read_pipe<R: tokio::io::AsyncRead + Unpin>(pipe: Option<R>) -> Vec<u8> {
-	use tokio::io::AsyncReadExt;
-	match pipe {
-		Some(mut p) => {
-			let mut buf = Vec::new();
-			let _ = p.read_to_end(&mut buf).await;
-			buf
-		}
-		None => Vec::new(),
-	}
-}
+use crate::notebook::CellMap;
+use crate::runner::evaluate::{
+	check_expected_files, evaluate_function_result, evaluate_io_output, matched_name,
+	stdout_mismatch, type_mismatch,
+};
+use crate::runner::executor::{BoxFuture, Executor};
+use crate::runner::process::{
+	CaseDir, SpawnError, absolute_path, find_student_file, missing_file_result,
+	resolve_program_path, sandboxed_command, setup_error_result, spawn_with_timeout,
+};
 
 /// Python helper script embedded in the binary.
 ///
 /// Takes a JSON payload on argv[1] with `file`, `function`, `args`.
 /// Outputs JSON on stdout: `{"ok": true, "value": ..., "type": "..."}` or
-/// `{"ok": false, "error_type": "...", "error_message": "..."}`.
+/// `{"ok": false, "error_type": "...", "error_message": "...", "traceback": "..."}`.
 const HELPER_SCRIPT: &str = r#"
-import importlib.util, sys, json, builtins, io, py_compile
+import importlib.util, sys, json, builtins, io, py_compile, inspect  # inspect: before the import guard
 
 payload = json.loads(sys.argv[1])
 file_path = payload["file"]
 func_name = payload["function"]
-args = payload["args"]
+
+def _sm_decode(obj):
+    """Decode tagged JSON into Python-only values: {"$tuple": [...]}, {"$set": [...]},
+    {"$frozenset": [...]}, {"$bytes": "text" or [ints]}, {"$float": "inf"/"-inf"/"nan"},
+    {"$none": true}."""
+    if isinstance(obj, list):
+        return [_sm_decode(v) for v in obj]
+    if isinstance(obj, dict):
+        if len(obj) == 1:
+            (tag, val), = obj.items()
+            if tag == "$tuple":
+                return tuple(_sm_decode(v) for v in val)
+            if tag == "$set":
+                return set(_sm_decode(v) for v in val)
+            if tag == "$frozenset":
+                return frozenset(_sm_decode(v) for v in val)
+            if tag == "$bytes":
+                return val.encode("utf-8") if isinstance(val, str) else bytes(val)
+            if tag == "$float":
+                return float(val)
+            if tag == "$none":
+                return None
+        return {k: _sm_decode(v) for k, v in obj.items()}
+    return obj
+
+args = _sm_decode(payload["args"])
+kwargs = _sm_decode(payload.get("kwargs", {}))
+
+def _sm_scripted_input(responses):
+    """input() that answers from the case's `inputs` in order, then raises EOFError."""
+    queue = list(responses)
+    def scripted_input(prompt=""):
+        sys.stdout.write(str(prompt))
+        if not queue:
+            raise EOFError(f"input() called more times than the case's {len(responses)} scripted inputs")
+        return queue.pop(0)
+    return scripted_input
+
+def _sm_traceback(e):
+    """Traceback lines for frames in the student file only."""
+    import traceback
+    frames = [f for f in traceback.extract_tb(e.__traceback__) if f.filename == file_path]
+    return "".join(traceback.format_list(frames))
 
 # Import guard: allowlist = safe stdlib + teacher-specified extras
 # Hardcoded because sys.stdlib_module_names is unavailable under env_clear()
@@ -139,10 +152,9 @@ try:
     spec.loader.exec_module(mod)
 except Exception as e:
     sys.stdout = _real_stdout
-    _real_print(json.dumps({"ok": False, "error_type": type(e).__name__, "error_message": str(e)}))
+    _real_print(json.dumps({"ok": False, "error_type": type(e).__name__, "error_message": str(e), "traceback": _sm_traceback(e)}))
     sys.exit(0)
 
-# Restore stdout for function execution — student function output is captured
 sys.stdout = _real_stdout
 
 def _fuzzy_lookup(module, name, expected_argc=None):
@@ -174,15 +186,51 @@ def _fuzzy_lookup(module, name, expected_argc=None):
         return best_obj, best_name
     return None, name
 
-func, matched_name = _fuzzy_lookup(mod, func_name, len(args))
+def _make_serializable(val):
+    """Best-effort conversion for JSON serialization. Tuples become lists and
+    sets sorted lists (the reported type keeps the difference); non-finite
+    floats and bytes use the same tags as inputs."""
+    if isinstance(val, float) and val != val:
+        return {"$float": "nan"}
+    if isinstance(val, float) and val in (float("inf"), float("-inf")):
+        return {"$float": "inf" if val > 0 else "-inf"}
+    if isinstance(val, (str, int, float, bool, type(None))):
+        return val
+    if isinstance(val, (bytes, bytearray)):
+        try:
+            return {"$bytes": bytes(val).decode("utf-8")}
+        except UnicodeDecodeError:
+            return {"$bytes": list(val)}
+    if isinstance(val, (list, tuple)):
+        return [_make_serializable(v) for v in val]
+    if isinstance(val, (set, frozenset)):
+        items = [_make_serializable(v) for v in val]
+        try:
+            return sorted(items)
+        except TypeError:
+            return sorted(items, key=lambda v: json.dumps(v, sort_keys=True))
+    if isinstance(val, dict):
+        return {str(k): _make_serializable(v) for k, v in val.items()}
+    return str(val)
+
+func, matched_name = _fuzzy_lookup(mod, func_name, len(args) + len(kwargs))
 if func is None:
     _real_print(json.dumps({"ok": False, "error_type": "AttributeError", "error_message": f"Function '{func_name}' not found"}))
     sys.exit(0)
+# Report a fuzzy substitution so the grader can show which function ran
+_sm_fuzzy = {"matched_name": matched_name} if matched_name != func_name else {}
+# Capture what the function prints; it is reported next to the return value
+builtins.input = _sm_scripted_input(payload.get("inputs", []))
+_sm_printed = io.StringIO()
 try:
-    result = func(*args)
-    _real_print(json.dumps({"ok": True, "value": result, "type": type(result).__name__}))
+    sys.stdout = _sm_printed
+    try:
+        result = func(*args, **kwargs)
+    finally:
+        sys.stdout = _real_stdout
+    _real_print(json.dumps({"ok": True, "value": _make_serializable(result), "type": type(result).__name__, "stdout": _sm_printed.getvalue(), **_sm_fuzzy}))
 except Exception as e:
-    _real_print(json.dumps({"ok": False, "error_type": type(e).__name__, "error_message": str(e)}))
+    _real_print(json.dumps({"ok": False, "error_type": type(e).__name__, "error_message": str(e), "traceback": _sm_traceback(e), "stdout": _sm_printed.getvalue(), **_sm_fuzzy}))
 "#;
 
 /// Chain helper script — runs teacher imports + setup + all cases in a single process.
@@ -194,12 +242,54 @@ except Exception as e:
 /// - Teacher `@checker` decorator: auto-discovered, dependency-injected from `_ctx`
 /// - `$ref` resolution in Python (live objects, no JSON round-trip)
 /// - `copy_refs` (default true): deepcopy `$ref` args per case to prevent mutation
+/// - Class cases: construct `class(*init_args)`, then run `calls` on the
+///   instance; the case's result carries one entry per call under `steps`
 const CHAIN_HELPER_SCRIPT: &str = r#"
-import importlib.util, sys, json, builtins, io, py_compile, inspect, copy
+import importlib.util, sys, json, builtins, io, py_compile, inspect, copy, os
 
 payload = json.loads(sys.argv[1])
 file_path = payload["file"]
 copy_refs = payload.get("copy_refs", True)
+
+def _sm_decode(obj):
+    """Decode tagged JSON into Python-only values: {"$tuple": [...]}, {"$set": [...]},
+    {"$frozenset": [...]}, {"$bytes": "text" or [ints]}, {"$float": "inf"/"-inf"/"nan"},
+    {"$none": true}."""
+    if isinstance(obj, list):
+        return [_sm_decode(v) for v in obj]
+    if isinstance(obj, dict):
+        if len(obj) == 1:
+            (tag, val), = obj.items()
+            if tag == "$tuple":
+                return tuple(_sm_decode(v) for v in val)
+            if tag == "$set":
+                return set(_sm_decode(v) for v in val)
+            if tag == "$frozenset":
+                return frozenset(_sm_decode(v) for v in val)
+            if tag == "$bytes":
+                return val.encode("utf-8") if isinstance(val, str) else bytes(val)
+            if tag == "$float":
+                return float(val)
+            if tag == "$none":
+                return None
+        return {k: _sm_decode(v) for k, v in obj.items()}
+    return obj
+
+def _sm_scripted_input(responses):
+    """input() that answers from the case's `inputs` in order, then raises EOFError."""
+    queue = list(responses)
+    def scripted_input(prompt=""):
+        sys.stdout.write(str(prompt))
+        if not queue:
+            raise EOFError(f"input() called more times than the case's {len(responses)} scripted inputs")
+        return queue.pop(0)
+    return scripted_input
+
+def _sm_traceback(e):
+    """Traceback lines for frames in the student file only."""
+    import traceback
+    frames = [f for f in traceback.extract_tb(e.__traceback__) if f.filename == file_path]
+    return "".join(traceback.format_list(frames))
 _ctx = {}
 _checkers = {}  # target_function_name -> checker_fn
 
@@ -230,18 +320,35 @@ def _resolve_refs(obj, do_copy=False):
         return obj
     if isinstance(obj, list):
         return [_resolve_refs(item, do_copy) for item in obj]
+    if isinstance(obj, tuple):
+        return tuple(_resolve_refs(item, do_copy) for item in obj)
     if isinstance(obj, dict):
         return {k: _resolve_refs(v, do_copy) for k, v in obj.items()}
     return obj
 
 def _make_serializable(val):
-    """Best-effort conversion for JSON serialization."""
+    """Best-effort conversion for JSON serialization. Tuples become lists and
+    sets sorted lists (the reported type keeps the difference); non-finite
+    floats and bytes use the same tags as inputs."""
+    if isinstance(val, float) and val != val:
+        return {"$float": "nan"}
+    if isinstance(val, float) and val in (float("inf"), float("-inf")):
+        return {"$float": "inf" if val > 0 else "-inf"}
     if isinstance(val, (str, int, float, bool, type(None))):
         return val
+    if isinstance(val, (bytes, bytearray)):
+        try:
+            return {"$bytes": bytes(val).decode("utf-8")}
+        except UnicodeDecodeError:
+            return {"$bytes": list(val)}
     if isinstance(val, (list, tuple)):
         return [_make_serializable(v) for v in val]
-    if isinstance(val, set):
-        return sorted(_make_serializable(v) for v in val)
+    if isinstance(val, (set, frozenset)):
+        items = [_make_serializable(v) for v in val]
+        try:
+            return sorted(items)
+        except TypeError:
+            return sorted(items, key=lambda v: json.dumps(v, sort_keys=True))
     if isinstance(val, dict):
         return {str(k): _make_serializable(v) for k, v in val.items()}
     return str(val)
@@ -392,51 +499,116 @@ for step in payload.get("setup", []):
     func, matched = _fuzzy_lookup(student_mod, fname, argc)
     if func is None:
         _fail(step["id"], "AttributeError", f"Function '{fname}' not found")
-    args = _resolve_refs(step.get("args", []))
+    args = _resolve_refs(_sm_decode(step.get("args", [])))
     try:
         _ctx[step["id"]] = func(*args)
     except Exception as e:
         _fail(step["id"], type(e).__name__, str(e))
 
-# 6. Run cases
-results = []
-for case in payload["cases"]:
-    fname = case["function"]
-    argc = len(case.get("args", []))
-    func, matched = _fuzzy_lookup(student_mod, fname, argc)
-    if func is None:
-        results.append({"ok": False, "name": case["name"],
-            "error_type": "AttributeError", "error_message": f"Function '{fname}' not found"})
-        continue
-    args = _resolve_refs(case.get("args", []), do_copy=copy_refs)
+def _call_args(spec):
+    """Positional and keyword args of a case or call: tags decoded, refs resolved."""
+    args = _resolve_refs(_sm_decode(spec.get("args", [])), do_copy=copy_refs)
+    kwargs = _resolve_refs(_sm_decode(spec.get("kwargs", {})), do_copy=copy_refs)
+    return args, kwargs
+
+def _run_call(name, fname, func, args, kwargs, spec):
+    """Call func(*args, **kwargs), capturing what it prints, and check the value
+    with an in-process checker, if any. Checkers can ask for `stdout`."""
+    printed = io.StringIO()
+    sys.stdout = printed
     try:
-        val = func(*args)
+        val = func(*args, **kwargs)
     except Exception as e:
-        results.append({"ok": False, "name": case["name"],
-            "error_type": type(e).__name__, "error_message": str(e)})
-        continue
+        return {"ok": False, "name": name,
+            "error_type": type(e).__name__, "error_message": str(e),
+            "traceback": _sm_traceback(e), "stdout": printed.getvalue()}
+    finally:
+        sys.stdout = _real_stdout
+    out = printed.getvalue()
 
     # In-process checker: explicit check_function > @checker decorator
     check_fn = None
-    cfn_name = case.get("check_function")
+    cfn_name = spec.get("check_function")
     if cfn_name:
         check_fn = _checkers.get(cfn_name) or _ctx.get(cfn_name)
     elif fname in _checkers:
         check_fn = _checkers[fname]
 
     if check_fn:
+        _ctx["stdout"] = out
         try:
-            passed, msg = _call_checker(check_fn, val, case.get("expected"))
-            results.append({"ok": bool(passed), "name": case["name"],
+            passed, msg = _call_checker(check_fn, val, spec.get("expected"))
+            return {"ok": bool(passed), "name": name,
                 "value": _make_serializable(val), "type": type(val).__name__,
-                "checked": True, "message": msg or ""})
+                "stdout": out, "checked": True, "message": msg or ""}
         except Exception as e:
-            results.append({"ok": False, "name": case["name"],
+            return {"ok": False, "name": name,
                 "value": _make_serializable(val), "type": type(val).__name__,
-                "checked": True, "message": f"Checker error: {type(e).__name__}: {e}"})
-    else:
-        results.append({"ok": True, "name": case["name"],
-            "value": _make_serializable(val), "type": type(val).__name__})
+                "stdout": out, "checked": True,
+                "message": f"Checker error: {type(e).__name__}: {e}"}
+    return {"ok": True, "name": name,
+        "value": _make_serializable(val), "type": type(val).__name__, "stdout": out}
+
+def _run_class_case(case):
+    """Construct the class, then run each method call on the same instance.
+    The constructor and the calls share the case's scripted inputs."""
+    cname = case["class"]
+    builtins.input = _sm_scripted_input(case.get("inputs", []))
+    init_args = _resolve_refs(_sm_decode(case.get("init_args", [])), do_copy=copy_refs)
+    cls, matched = _fuzzy_lookup(student_mod, cname, len(init_args))
+    if cls is None:
+        return {"ok": False, "name": case["name"],
+            "error_type": "AttributeError", "error_message": f"Class '{cname}' not found"}
+    sys.stdout = io.StringIO()  # constructor prints are not checked
+    try:
+        obj = cls(*init_args)
+    except Exception as e:
+        entry = {"ok": False, "name": case["name"],
+            "error_type": type(e).__name__, "error_message": str(e),
+            "traceback": _sm_traceback(e)}
+        if matched != cname:
+            entry["matched_name"] = matched
+        return entry
+    finally:
+        sys.stdout = _real_stdout
+    steps = []
+    for call in case["calls"]:
+        mname = call["method"]
+        args, kwargs = _call_args(call)
+        method, mmatched = _fuzzy_lookup(obj, mname, len(args) + len(kwargs))
+        if method is None:
+            steps.append({"ok": False, "name": call["name"], "error_type": "AttributeError",
+                "error_message": f"Method '{mname}' not found on {matched}"})
+            continue
+        step = _run_call(call["name"], mname, method, args, kwargs, call)
+        if matched != cname or mmatched != mname:
+            step["matched_name"] = f"{matched}.{mmatched}"
+        steps.append(step)
+    return {"ok": True, "name": case["name"], "steps": steps}
+
+# 6. Run cases, each in its own working dir with its extra env
+_sm_env = dict(os.environ)
+results = []
+for case in payload["cases"]:
+    os.chdir(case["cwd"])
+    os.environ.clear()
+    os.environ.update(_sm_env)
+    os.environ.update(case.get("env", {}))
+    if "class" in case:
+        results.append(_run_class_case(case))
+        continue
+    fname = case["function"]
+    args, kwargs = _call_args(case)
+    func, matched = _fuzzy_lookup(student_mod, fname, len(args) + len(kwargs))
+    if func is None:
+        results.append({"ok": False, "name": case["name"],
+            "error_type": "AttributeError", "error_message": f"Function '{fname}' not found"})
+        continue
+    builtins.input = _sm_scripted_input(case.get("inputs", []))
+    entry = _run_call(case["name"], fname, func, args, kwargs, case)
+    if matched != fname:
+        entry["matched_name"] = matched
+    results.append(entry)
 
 _real_print(json.dumps(results))
 "#;
@@ -450,7 +622,7 @@ pub struct PythonExecutor {
 impl PythonExecutor {
 	pub fn new() -> Self {
 		Self {
-			python_cmd: resolve_python_path("python3"),
+			python_cmd: resolve_program_path("python3"),
 			sandbox: crate::runner::sandbox::SandboxConfig::default(),
 		}
 	}
@@ -458,7 +630,7 @@ impl PythonExecutor {
 	pub fn with_python_cmd(python_cmd: impl Into<String>) -> Self {
 		let cmd: String = python_cmd.into();
 		Self {
-			python_cmd: resolve_python_path(&cmd),
+			python_cmd: resolve_program_path(&cmd),
 			sandbox: crate::runner::sandbox::SandboxConfig::default(),
 		}
 	}
@@ -469,139 +641,21 @@ impl PythonExecutor {
 
 	/// Build a sandboxed Command with env isolation and resource limits.
 	fn sandboxed_cmd(&self) -> Command {
-		let mut cmd = Command::new(&self.python_cmd);
-		cmd.env_clear()
-			.env("PATH", "/usr/bin:/usr/local/bin:/opt/homebrew/bin")
-			.env("HOME", "/tmp")
-			.env("PYTHONDONTWRITEBYTECODE", "1")
-			.stdout(std::process::Stdio::piped())
-			.stderr(std::process::Stdio::piped());
-		#[cfg(unix)]
-		apply_sandbox(&mut cmd, &self.sandbox);
+		let mut cmd = sandboxed_command(&self.python_cmd, &self.sandbox);
+		cmd.env("PYTHONDONTWRITEBYTECODE", "1");
 		cmd
 	}
 
-	/// Spawn a command with timeout, killing the child on timeout.
-	async fn spawn_with_timeout(
-		&self,
-		mut cmd: Command,
-		timeout_secs: u64,
-	) -> Result<Output, SpawnError> {
-		let mut child = cmd.spawn().map_err(SpawnError::Spawn)?;
-
-		// Take pipes and read concurrently with wait to avoid deadlock
-		// (if child fills the pipe buffer, it blocks until someone reads)
-		let stdout_pipe = child.stdout.take();
-		let stderr_pipe = child.stderr.take();
-
-		let timeout = std::time::Duration::from_secs(timeout_secs);
-		match tokio::time::timeout(timeout, async {
-			let (stdout, stderr, status) =
-				tokio::join!(read_pipe(stdout_pipe), read_pipe(stderr_pipe), child.wait(),);
-			status.map(|s| Output {
-				status: s,
-				stdout,
-				stderr,
-			})
-		})
-		.await
-		{
-			Ok(Ok(output)) => Ok(output),
-			Ok(Err(e)) => Err(SpawnError::Spawn(e)),
-			Err(_) => {
-				let _ = child.kill().await;
-				Err(SpawnError::Timeout)
-			}
-		}
-	}
-
-	/// Find the student file matching the spec's file pattern.
-	///
-	/// Strategy (scored, best wins):
-	/// 1. Exact suffix match (100) → highest confidence
-	/// 2. Strip numeric prefixes, compare stems (80-100)
-	/// 3. Stem-contains (40-60)
-	/// 4. Function-definition scan (+200 bonus) → if spec has function hint,
-	///    files containing `def function_name` get a large boost
+	/// Find the student file matching the spec's file pattern, preferring
+	/// files that define `function_hint`.
 	fn find_student_file_with_hint<'a>(
 		&self,
 		student_files: &'a [StudentFile],
 		pattern: &str,
 		function_hint: Option<&str>,
 	) -> Option<&'a StudentFile> {
-		let pattern_stem = Path::new(pattern)
-			.file_stem()
-			.and_then(|s| s.to_str())
-			.unwrap_or(pattern);
-
-		// 1. Exact suffix match — highest confidence
-		if let Some(f) = student_files.iter().find(|f| {
-			f.path
-				.file_name()
-				.and_then(|n| n.to_str())
-				.is_some_and(|n| n.ends_with(pattern))
-		}) {
-			return Some(f);
-		}
-
-		// Helper: strip numeric prefix segments (SID_uploadID_fileID_)
-		// "21300110043_171469_6012331_Lab3_2-2.py" → "Lab3_2-2.py"
-		fn extract_actual_name(filename: &str) -> &str {
-			let mut rest = filename;
-			loop {
-				if let Some(idx) = rest.find('_') {
-					let prefix = &rest[..idx];
-					if prefix.chars().all(|c| c.is_ascii_digit()) {
-						rest = &rest[idx + 1..];
-						continue;
-					}
-				}
-				break;
-			}
-			rest
-		}
-
-		// 2. Score all candidates by filename similarity + function content
-		let mut scored: Vec<(&'a StudentFile, u32)> = student_files
-			.iter()
-			.filter_map(|f| {
-				let filename = f.path.file_name()?.to_str()?;
-				let actual = extract_actual_name(filename);
-				let actual_stem = Path::new(actual)
-					.file_stem()
-					.and_then(|s| s.to_str())
-					.unwrap_or(actual);
-
-				let mut score: u32 = 0;
-
-				// --- Filename similarity ---
-				if actual_stem == pattern_stem {
-					score += 100;
-				} else if actual_stem.starts_with(pattern_stem) {
-					score += 80;
-				} else if actual.contains(pattern_stem) {
-					score += 60;
-				} else if filename.contains(pattern_stem) {
-					score += 40;
-				}
-
-				// --- Function definition scan (highest priority tiebreaker) ---
-				if let Some(func_name) = function_hint
-					&& let Ok(content) = std::fs::read_to_string(&f.path)
-				{
-					let needle = format!("def {func_name}");
-					if content.contains(&needle) {
-						score += 200; // trumps filename-only matches
-					}
-				}
-
-				if score > 0 { Some((f, score)) } else { None }
-			})
-			.collect();
-
-		scored.sort_by(|a, b| b.1.cmp(&a.1));
-
-		scored.first().map(|(f, _)| *f)
+		let needle = function_hint.map(|f| format!("def {f}"));
+		find_student_file(student_files, pattern, needle.as_deref())
 	}
 
 	/// Execute a function-call test case via the helper script.
@@ -620,14 +674,21 @@ impl PythonExecutor {
 			"file": student_file.path.to_string_lossy(),
 			"function": function_name,
 			"args": case.args,
+			"kwargs": case.kwargs,
+			"inputs": case.inputs,
 			"vars": vars,
 			"allowed_imports": allowed_imports,
 		});
 
+		let case_dir = match CaseDir::new(case) {
+			Ok(dir) => dir,
+			Err(e) => return setup_error_result(case, e),
+		};
 		let mut cmd = self.sandboxed_cmd();
 		cmd.arg("-c").arg(HELPER_SCRIPT).arg(payload.to_string());
+		case_dir.apply_function(&mut cmd, case);
 
-		let result = self.spawn_with_timeout(cmd, timeout_secs).await;
+		let result = spawn_with_timeout(cmd, None, timeout_secs).await;
 		let elapsed = start.elapsed().as_millis() as u64;
 
 		match result {
@@ -641,6 +702,7 @@ impl PythonExecutor {
 					details: String::new(),
 				}),
 				elapsed_ms: Some(elapsed),
+				matched_name: None,
 			},
 			Err(SpawnError::Spawn(e)) => CaseResult {
 				case_name: case.name.clone(),
@@ -652,138 +714,12 @@ impl PythonExecutor {
 					details: String::new(),
 				}),
 				elapsed_ms: Some(elapsed),
+				matched_name: None,
 			},
 			Ok(output) => {
 				let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-				self.evaluate_function_result(&stdout, case, elapsed)
-			}
-		}
-	}
-
-	/// Parse the JSON output from the helper script and check against expectations.
-	fn evaluate_function_result(
-		&self,
-		stdout: &str,
-		case: &TestCase,
-		elapsed_ms: u64,
-	) -> CaseResult {
-		let parsed: Result<serde_json::Value, _> = serde_json::from_str(stdout);
-
-		let json = match parsed {
-			Ok(v) => v,
-			Err(e) => {
-				return CaseResult {
-					case_name: case.name.clone(),
-					status: TestStatus::Error,
-					actual: Some(stdout.to_string()),
-					expected: None,
-					failure: Some(FailureDetail {
-						message: format!("Failed to parse helper output: {e}"),
-						details: stdout.to_string(),
-					}),
-					elapsed_ms: Some(elapsed_ms),
-				};
-			}
-		};
-
-		let ok = json.get("ok").and_then(|v| v.as_bool()).unwrap_or(false);
-
-		if !ok {
-			let error_type = json
-				.get("error_type")
-				.and_then(|v| v.as_str())
-				.unwrap_or("Unknown");
-			let error_message = json
-				.get("error_message")
-				.and_then(|v| v.as_str())
-				.unwrap_or("");
-
-			// Check if this was an expected error
-			if let Some(expected_error) = &case.expect_error
-				&& error_type == expected_error
-			{
-				return CaseResult {
-					case_name: case.name.clone(),
-					status: TestStatus::Passed,
-					actual: Some(format!("{error_type}: {error_message}")),
-					expected: Some(format!("{expected_error} (expected)")),
-					failure: None,
-					elapsed_ms: Some(elapsed_ms),
-				};
-			}
-
-			return CaseResult {
-				case_name: case.name.clone(),
-				status: TestStatus::Failed,
-				actual: Some(format!("{error_type}: {error_message}")),
-				expected: case
-					.expect
-					.as_ref()
-					.map(|v| v.to_string())
-					.or_else(|| case.expect_error.as_ref().map(|e| format!("{e} error"))),
-				failure: Some(FailureDetail {
-					message: format!("{error_type}: {error_message}"),
-					details: String::new(),
-				}),
-				elapsed_ms: Some(elapsed_ms),
-			};
-		}
-
-		// Student code returned successfully — but we expected an error?
-		if case.expect_error.is_some() {
-			let actual_value = json
-				.get("value")
-				.cloned()
-				.unwrap_or(serde_json::Value::Null);
-			return CaseResult {
-				case_name: case.name.clone(),
-				status: TestStatus::Failed,
-				actual: Some(actual_value.to_string()),
-				expected: case.expect_error.as_ref().map(|e| format!("{e} error")),
-				failure: Some(FailureDetail {
-					message: format!(
-						"Expected {} but function returned {}",
-						case.expect_error.as_deref().unwrap_or("error"),
-						actual_value
-					),
-					details: String::new(),
-				}),
-				elapsed_ms: Some(elapsed_ms),
-			};
-		}
-
-		// Normal return — check the value
-		let actual_value = json
-			.get("value")
-			.cloned()
-			.unwrap_or(serde_json::Value::Null);
-		let checker = self.resolve_checker(case);
-		let check_result = checker.check(&CheckInput {
-			result: actual_value.clone(),
-			expected: case.expect.clone().unwrap_or(serde_json::Value::Null),
-			context: serde_json::Value::Null,
-		});
-
-		if check_result.pass {
-			CaseResult {
-				case_name: case.name.clone(),
-				status: TestStatus::Passed,
-				actual: Some(actual_value.to_string()),
-				expected: case.expect.as_ref().map(|v| v.to_string()),
-				failure: None,
-				elapsed_ms: Some(elapsed_ms),
-			}
-		} else {
-			CaseResult {
-				case_name: case.name.clone(),
-				status: TestStatus::Failed,
-				actual: Some(actual_value.to_string()),
-				expected: case.expect.as_ref().map(|v| v.to_string()),
-				failure: Some(FailureDetail {
-					message: check_result.message,
-					details: String::new(),
-				}),
-				elapsed_ms: Some(elapsed_ms),
+				let result = evaluate_function_result(&stdout, case, elapsed, self.python_cmd());
+				check_expected_files(result, case, case_dir.path(), self.python_cmd())
 			}
 		}
 	}
@@ -799,175 +735,15 @@ impl PythonExecutor {
 
 		let mut cmd = self.sandboxed_cmd();
 		cmd.arg(&student_file.path);
+		let case_dir = match CaseDir::new(case) {
+			Ok(dir) => dir,
+			Err(e) => return setup_error_result(case, e),
+		};
+		case_dir.apply_io(&mut cmd, case);
 
-		if let Some(stdin_data) = &case.stdin {
-			cmd.stdin(std::process::Stdio::piped());
-			let mut child = match cmd.spawn() {
-				Ok(c) => c,
-				Err(e) => {
-					return CaseResult {
-						case_name: case.name.clone(),
-						status: TestStatus::Error,
-						actual: None,
-						expected: None,
-						failure: Some(FailureDetail {
-							message: format!("Failed to spawn: {e}"),
-							details: String::new(),
-						}),
-						elapsed_ms: Some(start.elapsed().as_millis() as u64),
-					};
-				}
-			};
-
-			// Write stdin then drop to close pipe
-			{
-				use tokio::io::AsyncWriteExt;
-				if let Some(child_stdin) = child.stdin.as_mut() {
-					let _ = child_stdin.write_all(stdin_data.as_bytes()).await;
-					let _ = child_stdin.shutdown().await;
-				}
-			}
-			child.stdin.take(); // close stdin
-
-			self.await_child_output(child, case, timeout_secs, start)
-				.await
-		} else {
-			let child = match cmd.spawn() {
-				Ok(c) => c,
-				Err(e) => {
-					return CaseResult {
-						case_name: case.name.clone(),
-						status: TestStatus::Error,
-						actual: None,
-						expected: None,
-						failure: Some(FailureDetail {
-							message: format!("Failed to spawn: {e}"),
-							details: String::new(),
-						}),
-						elapsed_ms: Some(start.elapsed().as_millis() as u64),
-					};
-				}
-			};
-
-			self.await_child_output(child, case, timeout_secs, start)
-				.await
-		}
-	}
-
-	/// Wait for a child process with timeout, then compare stdout.
-	/// Kills the child on timeout to prevent orphaned processes.
-	async fn await_child_output(
-		&self,
-		mut child: tokio::process::Child,
-		case: &TestCase,
-		timeout_secs: u64,
-		start: Instant,
-	) -> CaseResult {
-		// Take pipes and read concurrently with wait (same pattern as spawn_with_timeout)
-		let stdout_pipe = child.stdout.take();
-		let stderr_pipe = child.stderr.take();
-
-		let result = tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), async {
-			let (stdout, _stderr, status) =
-				tokio::join!(read_pipe(stdout_pipe), read_pipe(stderr_pipe), child.wait(),);
-			status.map(|s| Output {
-				status: s,
-				stdout,
-				stderr: _stderr,
-			})
-		})
-		.await;
-
-		let elapsed = start.elapsed().as_millis() as u64;
-
-		match result {
-			Err(_) => {
-				let _ = child.kill().await;
-				CaseResult {
-					case_name: case.name.clone(),
-					status: TestStatus::Timeout,
-					actual: None,
-					expected: case.expected_stdout.clone(),
-					failure: Some(FailureDetail {
-						message: format!("Timed out after {timeout_secs}s"),
-						details: String::new(),
-					}),
-					elapsed_ms: Some(elapsed),
-				}
-			}
-			Ok(Err(e)) => CaseResult {
-				case_name: case.name.clone(),
-				status: TestStatus::Error,
-				actual: None,
-				expected: None,
-				failure: Some(FailureDetail {
-					message: format!("Process error: {e}"),
-					details: String::new(),
-				}),
-				elapsed_ms: Some(elapsed),
-			},
-			Ok(Ok(output)) => {
-				let actual_stdout = String::from_utf8_lossy(&output.stdout).to_string();
-				let expected = case.expected_stdout.as_deref().unwrap_or("");
-
-				if actual_stdout == expected {
-					CaseResult {
-						case_name: case.name.clone(),
-						status: TestStatus::Passed,
-						actual: Some(actual_stdout),
-						expected: Some(expected.to_string()),
-						failure: None,
-						elapsed_ms: Some(elapsed),
-					}
-				} else {
-					CaseResult {
-						case_name: case.name.clone(),
-						status: TestStatus::Failed,
-						actual: Some(actual_stdout.clone()),
-						expected: Some(expected.to_string()),
-						failure: Some(FailureDetail {
-							message: "stdout mismatch".to_string(),
-							details: format!("expected:\n{expected}\nactual:\n{actual_stdout}"),
-						}),
-						elapsed_ms: Some(elapsed),
-					}
-				}
-			}
-		}
-	}
-
-	/// Resolve which checker to use for a test case.
-	fn resolve_checker(&self, case: &TestCase) -> Box<dyn Checker> {
-		use crate::checker::python_checker::PythonChecker;
-		use crate::checker::rhai_checker::RhaiChecker;
-		use crate::models::spec::CheckMethod;
-
-		if let Some(check) = &case.check {
-			match check {
-				CheckMethod::Builtin(name) => {
-					if let Some(c) = resolve_builtin(name, None) {
-						return c;
-					}
-				}
-				CheckMethod::Detailed(spec) => {
-					if let Some(name) = &spec.builtin
-						&& let Some(c) = resolve_builtin(name, spec.tolerance)
-					{
-						return c;
-					}
-					if let Some(expr) = &spec.rhai {
-						return Box::new(RhaiChecker::new(expr));
-					}
-					if let Some(script) = &spec.python {
-						return Box::new(
-							PythonChecker::new(script).with_python_cmd(self.python_cmd()),
-						);
-					}
-					// TODO: exec, wasm checkers
-				}
-			}
-		}
-		Box::new(ExactChecker)
+		let result = spawn_with_timeout(cmd, case.stdin.as_deref(), timeout_secs).await;
+		let result = evaluate_io_output(result, case, timeout_secs, start, self.python_cmd());
+		check_expected_files(result, case, case_dir.path(), self.python_cmd())
 	}
 
 	/// Execute all cases in chain mode (single Python subprocess).
@@ -995,53 +771,70 @@ impl PythonExecutor {
 				None => {
 					return cases
 						.iter()
-						.map(|c| CaseResult {
-							case_name: c.name.clone(),
-							status: TestStatus::Error,
-							actual: None,
-							expected: None,
-							failure: Some(FailureDetail {
-								message: format!(
-									"No file matching '{}' found in submission",
-									spec.meta.file
-								),
-								details: String::new(),
-							}),
-							elapsed_ms: Some(0),
-						})
+						.map(|c| missing_file_result(c, &spec.meta.file))
 						.collect();
 				}
 			};
 
+		// Each case runs in its own working dir with its `files` and `env`, and
+		// `expect_files` are checked there, as in per-case mode; cases whose dir
+		// cannot be set up are not sent
+		let dirs: Vec<Result<CaseDir, String>> = cases.iter().map(CaseDir::new).collect();
+		let runnable: Vec<(&TestCase, &CaseDir)> = cases
+			.iter()
+			.zip(&dirs)
+			.filter_map(|(c, dir)| Some((c, dir.as_ref().ok()?)))
+			.collect();
+
 		// Build case payloads — include check_function if specified via CheckSpec
-		let case_payloads: Vec<serde_json::Value> = cases
+		let case_payloads: Vec<serde_json::Value> = runnable
 			.iter()
-			.map(|c| {
+			.map(|&(c, dir)| {
 				let func_name = c
 					.function
 					.as_deref()
 					.or(spec.meta.function.as_deref())
 					.unwrap_or("__missing__");
 
-				let check_function = c.check.as_ref().and_then(|ch| {
-					if let crate::models::spec::CheckMethod::Detailed(spec) = ch {
-						spec.function.clone()
-					} else {
-						None
-					}
-				});
-
 				let mut obj = serde_json::json!({
 					"name": c.name,
 					"function": func_name,
 					"args": c.args,
+					"kwargs": c.kwargs,
+					"inputs": c.inputs,
+					"cwd": dir.path().to_string_lossy(),
+					"env": c.env,
 				});
 				if let Some(expected) = &c.expect {
 					obj["expected"] = expected.clone();
 				}
-				if let Some(cf) = check_function {
+				if let Some(cf) = check_function(c.check.as_ref()) {
 					obj["check_function"] = serde_json::Value::String(cf);
 				}
+				if let Some(class) = &c.class {
+					obj["class"] = serde_json::Value::String(class.clone());
+					obj["init_args"] = serde_json::json!(c.init_args);
+					obj["calls"] = c
+						.calls
+						.iter()
+						.enumerate()
+						.map(|(j, call)| {
+							let mut step = serde_json::json!({
+								"name": call.step_name(j),
+								"method": call.method,
+								"args": call.args,
+								"kwargs": call.kwargs,
+							});
+							if let Some(expected) = &call.expect {
+								step["expected"] = expected.clone();
+							}
+							if let Some(cf) = check_function(call.check.as_ref()) {
+								step["check_function"] = serde_json::Value::String(cf);
+							}
+							step
+						})
+						.collect();
+				}
 				obj
 			})
 			.collect();
@@ -1076,67 +869,102 @@ impl PythonExecutor {
 			.arg(CHAIN_HELPER_SCRIPT)
 			.arg(payload.to_string());
 
-		let result = self.spawn_with_timeout(cmd, timeout_secs).await;
+		let result = spawn_with_timeout(cmd, None, timeout_secs).await;
 		let elapsed = start.elapsed().as_millis() as u64;
 
-		match result {
-			Err(SpawnError::Timeout) => cases
+		let runnable_cases: Vec<&TestCase> = runnable.iter().map(|&(c, _)| c).collect();
+		let groups = match result {
+			Err(SpawnError::Timeout) => runnable_cases
 				.iter()
-				.map(|c| CaseResult {
-					case_name: c.name.clone(),
-					status: TestStatus::Timeout,
-					actual: None,
-					expected: c.expect.as_ref().map(|v| v.to_string()),
-					failure: Some(FailureDetail {
-						message: format!("Chain timed out after {timeout_secs}s"),
-						details: String::new(),
-					}),
-					elapsed_ms: Some(elapsed),
+				.map(|c| {
+					vec![CaseResult {
+						case_name: c.name.clone(),
+						status: TestStatus::Timeout,
+						actual: None,
+						expected: c.expect.as_ref().map(|v| v.to_string()),
+						failure: Some(FailureDetail {
+							message: format!("Chain timed out after {timeout_secs}s"),
+							details: String::new(),
+						}),
+						elapsed_ms: Some(elapsed),
+						matched_name: None,
+					}]
 				})
 				.collect(),
-			Err(SpawnError::Spawn(e)) => cases
+			Err(SpawnError::Spawn(e)) => runnable_cases
 				.iter()
-				.map(|c| CaseResult {
-					case_name: c.name.clone(),
-					status: TestStatus::Error,
-					actual: None,
-					expected: None,
-					failure: Some(FailureDetail {
-						message: format!("Failed to spawn python: {e}"),
-						details: String::new(),
-					}),
-					elapsed_ms: Some(elapsed),
+				.map(|c| {
+					vec![CaseResult {
+						case_name: c.name.clone(),
+						status: TestStatus::Error,
+						actual: None,
+						expected: None,
+						failure: Some(FailureDetail {
+							message: format!("Failed to spawn python: {e}"),
+							details: String::new(),
+						}),
+						elapsed_ms: Some(elapsed),
+						matched_name: None,
+					}]
 				})
 				.collect(),
 			Ok(output) => {
 				let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-				self.parse_chain_results(&stdout, cases, elapsed)
+				self.parse_chain_results(&stdout, &runnable_cases, elapsed)
+			}
+		};
+
+		let mut groups = groups.into_iter();
+		let mut results = Vec::new();
+		for (case, dir) in cases.iter().zip(&dirs) {
+			match dir {
+				Ok(dir) => {
+					// Files are checked once the case (every method call of a
+					// class case) has run
+					let mut group = groups.next().unwrap_or_default();
+					if let Some(last) = group.pop() {
+						group.push(check_expected_files(
+							last,
+							case,
+							dir.path(),
+							self.python_cmd(),
+						));
+					}
+					results.extend(group);
+				}
+				Err(e) => results.push(setup_error_result(case, e.clone())),
 			}
 		}
+		map_notebook_lines(student_file, &mut results);
+		results
 	}
 
-	/// Parse JSON output from the chain helper script.
+	/// Parse JSON output from the chain helper script into each case's
+	/// results (one per case, or one per method call for a class case).
 	fn parse_chain_results(
 		&self,
 		stdout: &str,
-		cases: &[TestCase],
+		cases: &[&TestCase],
 		elapsed_ms: u64,
-	) -> Vec<CaseResult> {
+	) -> Vec<Vec<CaseResult>> {
 		let parsed: serde_json::Value = match serde_json::from_str(stdout) {
 			Ok(v) => v,
 			Err(e) => {
 				return cases
 					.iter()
-					.map(|c| CaseResult {
-						case_name: c.name.clone(),
-						status: TestStatus::Error,
-						actual: Some(stdout.to_string()),
-						expected: None,
-						failure: Some(FailureDetail {
-							message: format!("Failed to parse chain output: {e}"),
-							details: stdout.to_string(),
-						}),
-						elapsed_ms: Some(elapsed_ms),
+					.map(|c| {
+						vec![CaseResult {
+							case_name: c.name.clone(),
+							status: TestStatus::Error,
+							actual: Some(stdout.to_string()),
+							expected: None,
+							failure: Some(FailureDetail {
+								message: format!("Failed to parse chain output: {e}"),
+								details: stdout.to_string(),
+							}),
+							elapsed_ms: Some(elapsed_ms),
+							matched_name: None,
+						}]
 					})
 					.collect();
 			}
@@ -1158,16 +986,19 @@ impl PythonExecutor {
 				.unwrap_or("");
 			return cases
 				.iter()
-				.map(|c| CaseResult {
-					case_name: c.name.clone(),
-					status: TestStatus::Error,
-					actual: None,
-					expected: None,
-					failure: Some(FailureDetail {
-						message: format!("Setup '{id}' failed: {error_type}: {error_message}"),
-						details: String::new(),
-					}),
-					elapsed_ms: Some(elapsed_ms),
+				.map(|c| {
+					vec![CaseResult {
+						case_name: c.name.clone(),
+						status: TestStatus::Error,
+						actual: None,
+						expected: None,
+						failure: Some(FailureDetail {
+							message: format!("Setup '{id}' failed: {error_type}: {error_message}"),
+							details: String::new(),
+						}),
+						elapsed_ms: Some(elapsed_ms),
+						matched_name: None,
+					}]
 				})
 				.collect();
 		}
@@ -1178,16 +1009,19 @@ impl PythonExecutor {
 			None => {
 				return cases
 					.iter()
-					.map(|c| CaseResult {
-						case_name: c.name.clone(),
-						status: TestStatus::Error,
-						actual: Some(stdout.to_string()),
-						expected: None,
-						failure: Some(FailureDetail {
-							message: "Chain output is not an array".to_string(),
-							details: stdout.to_string(),
-						}),
-						elapsed_ms: Some(elapsed_ms),
+					.map(|c| {
+						vec![CaseResult {
+							case_name: c.name.clone(),
+							status: TestStatus::Error,
+							actual: Some(stdout.to_string()),
+							expected: None,
+							failure: Some(FailureDetail {
+								message: "Chain output is not an array".to_string(),
+								details: stdout.to_string(),
+							}),
+							elapsed_ms: Some(elapsed_ms),
+							matched_name: None,
+						}]
 					})
 					.collect();
 			}
@@ -1196,122 +1030,141 @@ impl PythonExecutor {
 		cases
 			.iter()
 			.enumerate()
-			.map(|(i, case)| {
-				let entry = match results_array.get(i) {
-					Some(e) => e,
-					None => {
-						return CaseResult {
-							case_name: case.name.clone(),
-							status: TestStatus::Error,
-							actual: None,
-							expected: None,
-							failure: Some(FailureDetail {
-								message: "No result returned for this case".to_string(),
-								details: String::new(),
-							}),
-							elapsed_ms: Some(elapsed_ms),
-						};
-					}
-				};
-
-				// If Python-side checker already evaluated, use its verdict
-				if entry
-					.get("checked")
-					.and_then(|v| v.as_bool())
-					.unwrap_or(false)
-				{
-					let ok = entry.get("ok").and_then(|v| v.as_bool()).unwrap_or(false);
-					let msg = entry
-						.get("message")
-						.and_then(|v| v.as_str())
-						.unwrap_or("")
-						.to_string();
-					let actual = entry.get("value").map(|v| v.to_string());
-					if ok {
-						return CaseResult {
-							case_name: case.name.clone(),
-							status: TestStatus::Passed,
-							actual,
-							expected: case.expect.as_ref().map(|v| v.to_string()),
-							failure: None,
-							elapsed_ms: Some(elapsed_ms),
-						};
-					} else {
-						return CaseResult {
-							case_name: case.name.clone(),
-							status: TestStatus::Failed,
-							actual,
-							expected: case.expect.as_ref().map(|v| v.to_string()),
-							failure: Some(FailureDetail {
-								message: msg,
-								details: String::new(),
-							}),
-							elapsed_ms: Some(elapsed_ms),
-						};
-					}
-				}
-
-				// Otherwise, evaluate using Rust-side checker (same as single mode)
-				let ok = entry.get("ok").and_then(|v| v.as_bool()).unwrap_or(false);
-				if !ok {
-					// Build a fake stdout for evaluate_function_result
-					let json_str = entry.to_string();
-					return self.evaluate_function_result(&json_str, case, elapsed_ms);
+			.map(|(i, case)| match results_array.get(i) {
+				Some(entry) if case.class.is_some() => {
+					self.class_case_results(entry, case, elapsed_ms)
 				}
+				Some(entry) => vec![self.evaluate_chain_entry(entry, case, elapsed_ms)],
+				None => vec![no_chain_result(&case.name, elapsed_ms)],
+			})
+			.collect()
+	}
 
-				let actual_value = entry
-					.get("value")
-					.cloned()
-					.unwrap_or(serde_json::Value::Null);
+	/// Turn one chain helper entry into a result for `case`.
+	fn evaluate_chain_entry(
+		&self,
+		entry: &serde_json::Value,
+		case: &TestCase,
+		elapsed_ms: u64,
+	) -> CaseResult {
+		// If Python-side checker already evaluated, use its verdict
+		if entry
+			.get("checked")
+			.and_then(|v| v.as_bool())
+			.unwrap_or(false)
+		{
+			if let Some(mismatch) = type_mismatch(entry, case, elapsed_ms)
+				.or_else(|| stdout_mismatch(entry, case, elapsed_ms, self.python_cmd()))
+			{
+				return CaseResult {
+					matched_name: matched_name(entry),
+					..mismatch
+				};
+			}
+			let ok = entry.get("ok").and_then(|v| v.as_bool()).unwrap_or(false);
+			let msg = entry
+				.get("message")
+				.and_then(|v| v.as_str())
+				.unwrap_or("")
+				.to_string();
+			let actual = entry.get("value").map(|v| v.to_string());
+			if ok {
+				return CaseResult {
+					case_name: case.name.clone(),
+					status: TestStatus::Passed,
+					actual,
+					expected: case.expect.as_ref().map(|v| v.to_string()),
+					failure: None,
+					elapsed_ms: Some(elapsed_ms),
+					matched_name: matched_name(entry),
+				};
+			} else {
+				return CaseResult {
+					case_name: case.name.clone(),
+					status: TestStatus::Failed,
+					actual,
+					expected: case.expect.as_ref().map(|v| v.to_string()),
+					failure: Some(FailureDetail {
+						message: msg,
+						details: String::new(),
+					}),
+					elapsed_ms: Some(elapsed_ms),
+					matched_name: matched_name(entry),
+				};
+			}
+		}
 
-				// Check expect_error — function succeeded but we expected error
-				if case.expect_error.is_some() {
-					return CaseResult {
-						case_name: case.name.clone(),
-						status: TestStatus::Failed,
-						actual: Some(actual_value.to_string()),
-						expected: case.expect_error.as_ref().map(|e| format!("{e} error")),
-						failure: Some(FailureDetail {
-							message: format!(
-								"Expected {} but function returned {}",
-								case.expect_error.as_deref().unwrap_or("error"),
-								actual_value
-							),
-							details: String::new(),
-						}),
-						elapsed_ms: Some(elapsed_ms),
-					};
-				}
+		// Otherwise, evaluate using Rust-side checker (same as single mode)
+		evaluate_function_result(&entry.to_string(), case, elapsed_ms, self.python_cmd())
+	}
 
-				let checker = self.resolve_checker(case);
-				let check_result = checker.check(&CheckInput {
-					result: actual_value.clone(),
-					expected: case.expect.clone().unwrap_or(serde_json::Value::Null),
-					context: serde_json::Value::Null,
-				});
+	/// Expand a class case's entry into one sub-case per method call.
+	fn class_case_results(
+		&self,
+		entry: &serde_json::Value,
+		case: &TestCase,
+		elapsed_ms: u64,
+	) -> Vec<CaseResult> {
+		let step_name =
+			|j: usize, call: &MethodCall| format!("{} > {}", case.name, call.step_name(j));
+
+		let Some(steps) = entry.get("steps").and_then(|v| v.as_array()) else {
+			// Class missing or constructor raised; `expect_error` on the case
+			// can expect that
+			let result =
+				evaluate_function_result(&entry.to_string(), case, elapsed_ms, self.python_cmd());
+			if result.status == TestStatus::Passed {
+				return vec![result];
+			}
+			let class = case.class.as_deref().unwrap_or_default();
+			let failure = result.failure.clone().map(|f| FailureDetail {
+				message: format!("Constructing {class} failed: {}", f.message),
+				..f
+			});
+			return case
+				.calls
+				.iter()
+				.enumerate()
+				.map(|(j, call)| CaseResult {
+					case_name: step_name(j, call),
+					failure: failure.clone(),
+					..result.clone()
+				})
+				.collect();
+		};
 
-				if check_result.pass {
-					CaseResult {
-						case_name: case.name.clone(),
+		case.calls
+			.iter()
+			.enumerate()
+			.map(|(j, call)| {
+				let step_case = TestCase {
+					name: step_name(j, call),
+					expect: call.expect.clone(),
+					expect_error: call.expect_error.clone(),
+					expect_type: call.expect_type.clone(),
+					check: call.check.clone(),
+					..Default::default()
+				};
+				let Some(step) = steps.get(j) else {
+					return no_chain_result(&step_case.name, elapsed_ms);
+				};
+				let unchecked = call.expect.is_none()
+					&& call.check.is_none()
+					&& call.expect_error.is_none()
+					&& call.expect_type.is_none();
+				if unchecked && step.get("ok").and_then(|v| v.as_bool()) == Some(true) {
+					// Nothing to compare: the call only had to succeed
+					return CaseResult {
+						case_name: step_case.name,
 						status: TestStatus::Passed,
-						actual: Some(actual_value.to_string()),
-						expected: case.expect.as_ref().map(|v| v.to_string()),
+						actual: step.get("value").map(|v| v.to_string()),
+						expected: None,
 						failure: None,
 						elapsed_ms: Some(elapsed_ms),
-					}
-				} else {
-					CaseResult {
-						case_name: case.name.clone(),
-						status: TestStatus::Failed,
-						actual: Some(actual_value.to_string()),
-						expected: case.expect.as_ref().map(|v| v.to_string()),
-						failure: Some(FailureDetail {
-							message: check_result.message,
-							details: String::new(),
-						}),
-						elapsed_ms: Some(elapsed_ms),
-					}
+						matched_name: matched_name(step),
+					};
 				}
+				self.evaluate_chain_entry(step, &step_case, elapsed_ms)
 			})
 			.collect()
 	}
@@ -1330,25 +1183,15 @@ impl PythonExecutor {
 			spec.meta.function.as_deref(),
 		) {
 			Some(f) => f,
-			None => {
-				return CaseResult {
-					case_name: case.name.clone(),
-					status: TestStatus::Error,
-					actual: None,
-					expected: None,
-					failure: Some(FailureDetail {
-						message: format!(
-							"No file matching '{}' found in submission",
-							spec.meta.file
-						),
-						details: String::new(),
-					}),
-					elapsed_ms: Some(0),
-				};
-			}
+			None => return missing_file_result(case, &spec.meta.file),
+		};
+		// Absolute, since each case runs in its own working directory
+		let student_file = &StudentFile {
+			path: absolute_path(&student_file.path),
+			..student_file.clone()
 		};
 
-		if let Some(function_name) = &spec.meta.function {
+		let mut result = if let Some(function_name) = &spec.meta.function {
 			self.execute_function_call(
 				student_file,
 				function_name,
@@ -1360,7 +1203,56 @@ impl PythonExecutor {
 			.await
 		} else {
 			self.execute_io_test(student_file, case, timeout_secs).await
+		};
+		map_notebook_lines(student_file, std::slice::from_mut(&mut result));
+		result
+	}
+}
+
+/// In-process checker function named by a `check = { function = ... }` spec.
+fn check_function(check: Option<&CheckMethod>) -> Option<String> {
+	match check {
+		Some(CheckMethod::Detailed(spec)) => spec.function.clone(),
+		_ => None,
+	}
+}
+
+/// Error result for a case (or method call) the chain helper skipped.
+fn no_chain_result(name: &str, elapsed_ms: u64) -> CaseResult {
+	CaseResult {
+		case_name: name.to_string(),
+		status: TestStatus::Error,
+		actual: None,
+		expected: None,
+		failure: Some(FailureDetail {
+			message: "No result returned for this case".to_string(),
+			details: String::new(),
+		}),
+		elapsed_ms: Some(elapsed_ms),
+		matched_name: None,
+	}
+}
+
+/// Point line numbers in failures at notebook cells when the student file
+/// was converted from `.ipynb`.
+pub(crate) fn map_notebook_lines(student_file: &StudentFile, results: &mut [CaseResult]) {
+	if results.iter().all(|r| r.failure.is_none()) {
+		return;
+	}
+	let Some(map) = CellMap::from_path(&student_file.path) else {
+		return;
+	};
+	for result in results {
+		let Some(failure) = &mut result.failure else {
+			continue;
+		};
+		let message = map.rewrite(&failure.message, &student_file.path);
+		// Errors repeat the message as `actual`; student stdout is left alone
+		if result.actual.as_deref() == Some(failure.message.as_str()) {
+			result.actual = Some(message.clone());
 		}
+		failure.message = message;
+		failure.details = map.rewrite(&failure.details, &student_file.path);
 	}
 }
 
@@ -1369,3 +1261,45 @@ impl Default for PythonExecutor {
 		Self::new()
 	}
 }
+
+impl Executor for PythonExecutor {
+	fn language(&self) -> &str {
+		"python"
+	}
+
+	fn python_cmd(&self) -> &str {
+		&self.python_cmd
+	}
+
+	fn execute_case<'a>(
+		&'a self,
+		student_files: &'a [StudentFile],
+		spec: &'a TestSpec,
+		case: &'a TestCase,
+		timeout_secs: u64,
+	) -> BoxFuture<'a, CaseResult> {
+		Box::pin(PythonExecutor::execute_case(
+			self,
+			student_files,
+			spec,
+			case,
+			timeout_secs,
+		))
+	}
+
+	fn execute_chain<'a>(
+		&'a self,
+		student_files: &'a [StudentFile],
+		spec: &'a TestSpec,
+		cases: &'a [TestCase],
+		timeout_secs: u64,
+	) -> BoxFuture<'a, Vec<CaseResult>> {
+		Box::pin(PythonExecutor::execute_chain(
+			self,
+			student_files,
+			spec,
+			cases,
+			timeout_secs,
+		))
+	}
+}
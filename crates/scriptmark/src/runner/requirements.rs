@@ -0,0 +1,388 @@
+use std::time::Instant;
+
+use crate::models::{CaseResult, FailureDetail, Requirements, StudentFile, TestSpec, TestStatus};
+use crate::runner::process::{
+	SpawnError, find_student_file, resolve_program_path, sandboxed_command, spawn_with_timeout,
+};
+use crate::runner::python::map_notebook_lines;
+use crate::runner::sandbox::SandboxConfig;
+
+/// Walks the student's AST and lists the violations of each rule, in order.
+///
+/// Takes a JSON payload on argv[1] with `file` and `rules`
+/// (`[{"kind": ..., "target": ...}]`). Prints `{"violations": [...]}`, one
+/// list of messages (or `{"error": ...}`) per rule, or `{"error": "..."}` when
+/// the file cannot be parsed. The student code is parsed, never run.
+const REQUIREMENTS_SCRIPT: &str = r#"
+import ast, json, sys
+
+payload = json.loads(sys.argv[1])
+try:
+    with open(payload["file"], encoding="utf-8") as f:
+        tree = ast.parse(f.read(), filename=payload["file"])
+except (OSError, SyntaxError, ValueError) as e:
+    print(json.dumps({"error": f"{type(e).__name__}: {e}"}))
+    sys.exit(0)
+
+def call_name(node):
+    func = node.func
+    if isinstance(func, ast.Name):
+        return func.id
+    if isinstance(func, ast.Attribute):
+        return func.attr
+    return None
+
+def forbidden_call(name):
+    return [f"{name}() called on line {n.lineno}" for n in ast.walk(tree)
+            if isinstance(n, ast.Call) and call_name(n) == name]
+
+def forbidden_import(module):
+    found = []
+    for n in ast.walk(tree):
+        if isinstance(n, ast.Import):
+            names = [a.name for a in n.names]
+        elif isinstance(n, ast.ImportFrom) and n.level == 0 and n.module:
+            names = [n.module]
+        else:
+            continue
+        found += [f"imports {m} on line {n.lineno}" for m in names
+                  if m == module or m.startswith(module + ".")]
+    return found
+
+def forbidden_node(kind):
+    if not isinstance(getattr(ast, kind, None), type):
+        return {"error": f"unknown ast node type '{kind}'"}
+    return [f"{kind} on line {n.lineno}" if hasattr(n, "lineno") else kind
+            for n in ast.walk(tree) if type(n).__name__ == kind]
+
+def recursive(name):
+    defs = [n for n in ast.walk(tree)
+            if isinstance(n, (ast.FunctionDef, ast.AsyncFunctionDef)) and n.name == name]
+    if not defs:
+        return [f"function {name} not found"]
+    for d in defs:
+        if any(isinstance(n, ast.Call) and call_name(n) == name for n in ast.walk(d)):
+            return []
+    return [f"{name} does not call itself"]
+
+BLOCKS = tuple(getattr(ast, t) for t in
+    ("For", "AsyncFor", "While", "If", "With", "AsyncWith", "Try", "TryStar", "Match")
+    if hasattr(ast, t))
+
+def max_nesting(limit):
+    found = []
+    def visit(node, depth):
+        for child in ast.iter_child_nodes(node):
+            is_elif = isinstance(node, ast.If) and node.orelse == [child] and isinstance(child, ast.If)
+            d = depth + 1 if isinstance(child, BLOCKS) and not is_elif else depth
+            if d == limit + 1 and d > depth:
+                found.append(f"depth {d} on line {child.lineno}")
+            visit(child, d)
+    visit(tree, 0)
+    return found
+
+CHECKS = {"call": forbidden_call, "import": forbidden_import, "node": forbidden_node,
+    "recursive": recursive, "max_nesting": max_nesting}
+print(json.dumps({"violations": [CHECKS[r["kind"]](r["target"]) for r in payload["rules"]]}))
+"#;
+
+/// One rule of a `[requirements]` section.
+enum Rule<'a> {
+	Call(&'a str),
+	Import(&'a str),
+	Node(&'a str),
+	Recursive(&'a str),
+	MaxNesting(usize),
+}
+
+impl Rule<'_> {
+	fn case_name(&self) -> String {
+		match self {
+			Rule::Call(name) => format!("requirement: no {name}()"),
+			Rule::Import(module) => format!("requirement: no import {module}"),
+			Rule::Node(kind) => format!("requirement: no {kind}"),
+			Rule::Recursive(name) => format!("requirement: {name} is recursive"),
+			Rule::MaxNesting(depth) => format!("requirement: nesting depth <= {depth}"),
+		}
+	}
+
+	fn payload(&self) -> serde_json::Value {
+		let (kind, target) = match self {
+			Rule::Call(name) => ("call", serde_json::json!(name)),
+			Rule::Import(module) => ("import", serde_json::json!(module)),
+			Rule::Node(kind) => ("node", serde_json::json!(kind)),
+			Rule::Recursive(name) => ("recursive", serde_json::json!(name)),
+			Rule::MaxNesting(depth) => ("max_nesting", serde_json::json!(depth)),
+		};
+		serde_json::json!({ "kind": kind, "target": target })
+	}
+}
+
+fn rules(req: &Requirements) -> Vec<Rule<'_>> {
+	let mut rules: Vec<Rule> = Vec::new();
+	rules.extend(req.forbidden_calls.iter().map(|n| Rule::Call(n)));
+	rules.extend(req.forbidden_imports.iter().map(|m| Rule::Import(m)));
+	rules.extend(req.forbidden_nodes.iter().map(|k| Rule::Node(k)));
+	rules.extend(req.recursive.iter().map(|n| Rule::Recursive(n)));
+	rules.extend(req.max_nesting.map(Rule::MaxNesting));
+	rules
+}
+
+/// Check `spec.requirements` against the student's file, one case per rule.
+///
+/// Rules with no violations pass; otherwise the case fails listing each
+/// violation with its line. If the file is missing or cannot be parsed,
+/// every rule is an error. The analysis script runs under `python_cmd`.
+pub async fn check_requirements(
+	files: &[StudentFile],
+	spec: &TestSpec,
+	python_cmd: &str,
+	timeout_secs: u64,
+) -> Vec<CaseResult> {
+	let Some(req) = &spec.requirements else {
+		return Vec::new();
+	};
+	let rules = rules(req);
+	if rules.is_empty() {
+		return Vec::new();
+	}
+
+	if spec.meta.language != "python" {
+		return error_results(
+			&rules,
+			format!(
+				"Code requirements are not supported for {}",
+				spec.meta.language
+			),
+		);
+	}
+	let needle = spec.meta.function.as_ref().map(|f| format!("def {f}"));
+	let Some(file) = find_student_file(files, &spec.meta.file, needle.as_deref()) else {
+		return error_results(
+			&rules,
+			format!("No file matching '{}' found in submission", spec.meta.file),
+		);
+	};
+
+	let mut results = check_file(file, &rules, python_cmd, timeout_secs).await;
+	map_notebook_lines(file, &mut results);
+	results
+}
+
+/// Every rule as an error with the same message.
+fn error_results(rules: &[Rule], message: String) -> Vec<CaseResult> {
+	rules
+		.iter()
+		.map(|rule| CaseResult {
+			case_name: rule.case_name(),
+			status: TestStatus::Error,
+			actual: None,
+			expected: None,
+			failure: Some(FailureDetail {
+				message: message.clone(),
+				details: String::new(),
+			}),
+			elapsed_ms: Some(0),
+			matched_name: None,
+		})
+		.collect()
+}
+
+/// Run the analysis script over `file`, one result per rule.
+async fn check_file(
+	file: &StudentFile,
+	rules: &[Rule<'_>],
+	python_cmd: &str,
+	timeout_secs: u64,
+) -> Vec<CaseResult> {
+	let start = Instant::now();
+	let all_error = |message: String| error_results(rules, message);
+
+	let payload = serde_json::json!({
+		"file": file.path.to_string_lossy(),
+		"rules": rules.iter().map(Rule::payload).collect::<Vec<_>>(),
+	});
+	let mut cmd = sandboxed_command(resolve_program_path(python_cmd), &SandboxConfig::default());
+	cmd.arg("-c")
+		.arg(REQUIREMENTS_SCRIPT)
+		.arg(payload.to_string());
+	let output = match spawn_with_timeout(cmd, None, timeout_secs).await {
+		Ok(output) => output,
+		Err(SpawnError::Timeout) => {
+			return all_error(format!("Requirement check timed out after {timeout_secs}s"));
+		}
+		Err(SpawnError::Spawn(e)) => return all_error(format!("Failed to spawn python: {e}")),
+	};
+	let elapsed = start.elapsed().as_millis() as u64;
+
+	let parsed: serde_json::Value = match serde_json::from_slice(&output.stdout) {
+		Ok(v) => v,
+		Err(e) => {
+			return all_error(format!(
+				"Failed to parse requirement check output: {e}\n{}",
+				String::from_utf8_lossy(&output.stderr).trim()
+			));
+		}
+	};
+	if let Some(error) = parsed.get("error").and_then(|e| e.as_str()) {
+		return all_error(format!("Cannot check requirements: {error}"));
+	}
+	let empty = Vec::new();
+	let violations = parsed
+		.get("violations")
+		.and_then(|v| v.as_array())
+		.unwrap_or(&empty);
+
+	rules
+		.iter()
+		.enumerate()
+		.map(|(i, rule)| {
+			let entry = violations.get(i);
+			let (status, message) = match entry {
+				Some(serde_json::Value::Array(found)) if found.is_empty() => {
+					(TestStatus::Passed, None)
+				}
+				Some(serde_json::Value::Array(found)) => {
+					let found: Vec<&str> = found.iter().filter_map(|v| v.as_str()).collect();
+					(TestStatus::Failed, Some(found.join("; ")))
+				}
+				Some(other) => (
+					TestStatus::Error,
+					Some(
+						other
+							.get("error")
+							.and_then(|e| e.as_str())
+							.unwrap_or("invalid requirement result")
+							.to_string(),
+					),
+				),
+				None => (
+					TestStatus::Error,
+					Some("No result from requirement check".to_string()),
+				),
+			};
+			CaseResult {
+				case_name: rule.case_name(),
+				status,
+				actual: None,
+				expected: None,
+				failure: message.map(|message| FailureDetail {
+					message,
+					details: String::new(),
+				}),
+				elapsed_ms: Some(elapsed),
+				matched_name: None,
+			}
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn spec(toml_str: &str) -> TestSpec {
+		toml::from_str(toml_str).unwrap()
+	}
+
+	fn py_file(dir: &std::path::Path, code: &str) -> Vec<StudentFile> {
+		let path = dir.join("lab3.py");
+		std::fs::write(&path, code).unwrap();
+		vec![StudentFile {
+			path,
+			language: "python".to_string(),
+		}]
+	}
+
+	const SPEC: &str = r#"
+[meta]
+name = "lab3"
+file = "lab3.py"
+function = "fact"
+language = "python"
+
+[requirements]
+forbidden_calls = ["sorted"]
+forbidden_imports = ["math"]
+forbidden_nodes = ["While"]
+recursive = ["fact"]
+max_nesting = 2
+
+[[cases]]
+name = "fact 3"
+args = [3]
+expect = 6
+"#;
+
+	#[tokio::test]
+	async fn test_requirements_pass_and_fail() {
+		let dir = tempfile::tempdir().unwrap();
+		let spec = spec(SPEC);
+
+		let good = py_file(
+			dir.path(),
+			"def fact(n):\n    if n <= 1:\n        return 1\n    elif n > 100:\n        if n > 1000:\n            return 0\n    return n * fact(n - 1)\n",
+		);
+		let results = check_requirements(&good, &spec, "python3", 10).await;
+		assert_eq!(results.len(), 5);
+		for r in &results {
+			assert_eq!(r.status, TestStatus::Passed, "{r:?}");
+		}
+
+		let bad = py_file(
+			dir.path(),
+			"import math.fsum\n\ndef fact(n):\n    r = 1\n    for i in sorted(range(n)):\n        while True:\n            if i:\n                r *= i + 1\n            break\n    return r\n",
+		);
+		let results = check_requirements(&bad, &spec, "python3", 10).await;
+		let messages: Vec<_> = results
+			.iter()
+			.map(|r| (r.status, r.failure.as_ref().map(|f| f.message.as_str())))
+			.collect();
+		assert_eq!(
+			messages,
+			[
+				(TestStatus::Failed, Some("sorted() called on line 5")),
+				(TestStatus::Failed, Some("imports math.fsum on line 1")),
+				(TestStatus::Failed, Some("While on line 6")),
+				(TestStatus::Failed, Some("fact does not call itself")),
+				(TestStatus::Failed, Some("depth 3 on line 7")),
+			]
+		);
+		assert_eq!(results[4].case_name, "requirement: nesting depth <= 2");
+	}
+
+	#[tokio::test]
+	async fn test_requirements_syntax_error_and_unknown_node() {
+		let dir = tempfile::tempdir().unwrap();
+		let files = py_file(dir.path(), "def fact(n)\n    return 1\n");
+		let results = check_requirements(&files, &spec(SPEC), "python3", 10).await;
+		assert!(results.iter().all(|r| r.status == TestStatus::Error));
+		assert!(
+			results[0]
+				.failure
+				.as_ref()
+				.unwrap()
+				.message
+				.starts_with("Cannot check requirements: SyntaxError")
+		);
+
+		let files = py_file(dir.path(), "x = 1\n");
+		let spec = spec(&SPEC.replace("\"While\"", "\"while\""));
+		let results = check_requirements(&files, &spec, "python3", 10).await;
+		assert_eq!(results[2].status, TestStatus::Error);
+		assert_eq!(
+			results[2].failure.as_ref().unwrap().message,
+			"unknown ast node type 'while'"
+		);
+
+		let results = check_requirements(&files, &spec, "/nonexistent/python3", 10).await;
+		assert!(
+			results[0]
+				.failure
+				.as_ref()
+				.unwrap()
+				.message
+				.starts_with("Failed to spawn python")
+		);
+	}
+}
@@ -36,6 +36,30 @@ impl Default for SandboxConfig {
 /// student Python process.
 #[cfg(unix)]
 pub fn apply_sandbox(cmd: &mut tokio::process::Command, config: &SandboxConfig) {
+	let hook = rlimit_hook(config);
+	// SAFETY: setrlimit is async-signal-safe and we only call it
+	// in the child process between fork() and exec().
+	unsafe {
+		cmd.pre_exec(hook);
+	}
+}
+
+/// [`apply_sandbox`] for a blocking `std::process::Command` (used by checkers).
+#[cfg(unix)]
+pub fn apply_sandbox_std(cmd: &mut std::process::Command, config: &SandboxConfig) {
+	use std::os::unix::process::CommandExt;
+
+	let hook = rlimit_hook(config);
+	// SAFETY: as in `apply_sandbox`.
+	unsafe {
+		cmd.pre_exec(hook);
+	}
+}
+
+#[cfg(unix)]
+fn rlimit_hook(
+	config: &SandboxConfig,
+) -> impl FnMut() -> std::io::Result<()> + Send + Sync + 'static {
 	let cpu = config.cpu_secs;
 	#[cfg(not(target_os = "macos"))]
 	let mem = config.mem_mb * 1024 * 1024;
@@ -43,21 +67,17 @@ pub fn apply_sandbox(cmd: &mut tokio::process::Command, config: &SandboxConfig)
 	let nofile = config.nofile;
 	let nproc = config.nproc;
 
-	// SAFETY: setrlimit is async-signal-safe and we only call it
-	// in the child process between fork() and exec().
-	unsafe {
-		cmd.pre_exec(move || {
-			// Best-effort: some limits may not be supported on all platforms
-			// (e.g. RLIMIT_AS on macOS, RLIMIT_NPROC when user has many processes)
-			let _ = set_rlimit(libc::RLIMIT_CPU, cpu);
-			let _ = set_rlimit(libc::RLIMIT_FSIZE, fsize);
-			let _ = set_rlimit(libc::RLIMIT_NOFILE, nofile);
-			let _ = set_rlimit(libc::RLIMIT_NPROC, nproc);
-			// RLIMIT_AS: skip on macOS where it's unreliable
-			#[cfg(not(target_os = "macos"))]
-			let _ = set_rlimit(libc::RLIMIT_AS, mem);
-			Ok(())
-		});
+	move || {
+		// Best-effort: some limits may not be supported on all platforms
+		// (e.g. RLIMIT_AS on macOS, RLIMIT_NPROC when user has many processes)
+		let _ = set_rlimit(libc::RLIMIT_CPU, cpu);
+		let _ = set_rlimit(libc::RLIMIT_FSIZE, fsize);
+		let _ = set_rlimit(libc::RLIMIT_NOFILE, nofile);
+		let _ = set_rlimit(libc::RLIMIT_NPROC, nproc);
+		// RLIMIT_AS: skip on macOS where it's unreliable
+		#[cfg(not(target_os = "macos"))]
+		let _ = set_rlimit(libc::RLIMIT_AS, mem);
+		Ok(())
 	}
 }
 
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use crate::models::{AssignmentConfig, CourseConfig, TestSpec};
+use crate::models::{AssignmentConfig, CaseFile, CourseConfig, ExpectFile, TestSpec};
 
 /// Load a test specification from a TOML file.
 ///
@@ -26,6 +26,35 @@ pub fn load_spec(path: &Path) -> Result<TestSpec, SpecError> {
 				}
 			})
 			.collect();
+
+		// Same for files copied into IO cases' working directories
+		for case in &mut spec.cases {
+			for file in case.files.values_mut() {
+				if let CaseFile::Copy { source } = file
+					&& Path::new(source.as_str()).is_relative()
+				{
+					*source = parent.join(&*source).to_string_lossy().to_string();
+				}
+			}
+		}
+	}
+
+	for case in &spec.cases {
+		let file_checks = case.expect_files.values().filter_map(|f| match f {
+			ExpectFile::Checked { check, .. } => check.as_deref(),
+			ExpectFile::Content(_) => None,
+		});
+		for check in case
+			.check
+			.iter()
+			.chain(&case.stderr_check)
+			.chain(file_checks)
+			.chain(case.calls.iter().filter_map(|c| c.check.as_ref()))
+		{
+			crate::checker::validate_check(check).map_err(|msg| {
+				SpecError::InvalidCheck(path.to_path_buf(), case.name.clone(), msg)
+			})?;
+		}
 	}
 
 	Ok(spec)
@@ -81,6 +110,8 @@ pub enum SpecError {
 	IoError(std::path::PathBuf, std::io::Error),
 	#[error("TOML parse error in {0}: {1}")]
 	ParseError(std::path::PathBuf, toml::de::Error),
+	#[error("invalid check in {0}, case '{1}': {2}")]
+	InvalidCheck(std::path::PathBuf, String, String),
 }
 
 #[cfg(test)]
@@ -127,6 +158,31 @@ expect_error = "TypeError"
 		assert_eq!(spec.cases[2].expect_error.as_deref(), Some("TypeError"));
 	}
 
+	#[test]
+	fn test_load_spec_rejects_invalid_regex() {
+		let dir = tempfile::tempdir().unwrap();
+		let spec_path = dir.path().join("test_regex.toml");
+		std::fs::write(
+			&spec_path,
+			r#"
+[meta]
+name = "greeting"
+file = "hello.py"
+language = "python"
+
+[[cases]]
+name = "says hello"
+expected_stdout = ""
+check = { regex = "hello(", flags = "i" }
+"#,
+		)
+		.unwrap();
+
+		let err = load_spec(&spec_path).unwrap_err();
+		assert!(matches!(err, SpecError::InvalidCheck(_, ref case, _) if case == "says hello"));
+		assert!(err.to_string().contains("invalid regex"), "{err}");
+	}
+
 	#[test]
 	fn test_load_course_config() {
 		let dir = tempfile::tempdir().unwrap();
@@ -136,10 +136,8 @@ impl App {
 					self.selected += 1;
 				}
 			}
-			KeyCode::Up | KeyCode::Char('k') => {
-				if self.selected > 0 {
-					self.selected -= 1;
-				}
+			KeyCode::Up | KeyCode::Char('k') if self.selected > 0 => {
+				self.selected -= 1;
 			}
 			KeyCode::Enter => match self.tab {
 				Tab::Students => {
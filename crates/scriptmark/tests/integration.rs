@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::io::Write;
 
 use scriptmark::models::*;
+use scriptmark::runner::executor::ExecutorRegistry;
 use scriptmark::runner::orchestrator;
 use scriptmark::runner::python::PythonExecutor;
 
@@ -153,7 +154,7 @@ async fn test_python_executor_buggy_student() {
 #[tokio::test]
 async fn test_orchestrator_runs_all_students() {
 	let dir = setup_test_dir();
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([
@@ -176,7 +177,7 @@ async fn test_orchestrator_runs_all_students() {
 
 	let specs = vec![test_spec()];
 
-	let results = orchestrator::run_all(&submissions, &specs, &executor, 10, Some(2)).await;
+	let results = orchestrator::run_all(&submissions, &specs, &executors, 10, Some(2)).await;
 
 	assert_eq!(results.len(), 2);
 	assert!(results.contains_key("alice"));
@@ -194,6 +195,44 @@ async fn test_orchestrator_runs_all_students() {
 	assert_eq!(bob.total_passed(), 2);
 }
 
+#[tokio::test]
+async fn test_orchestrator_dispatches_by_language() {
+	let dir = setup_test_dir();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
+
+	let submissions = SubmissionSet {
+		by_student: HashMap::from([(
+			"alice".to_string(),
+			vec![StudentFile {
+				path: dir.path().join("alice_lab5.py"),
+				language: "python".to_string(),
+			}],
+		)]),
+	};
+
+	let mut cobol_spec = test_spec();
+	cobol_spec.meta.name = "cobol".to_string();
+	cobol_spec.meta.language = "cobol".to_string();
+	let specs = vec![test_spec(), cobol_spec];
+
+	let results = orchestrator::run_all(&submissions, &specs, &executors, 10, Some(1)).await;
+	let alice = &results["alice"];
+	assert_eq!(alice.backend_name.as_deref(), Some("python"));
+	assert_eq!(alice.test_results[0].passed(), 4);
+
+	let cobol = &alice.test_results[1];
+	assert_eq!(cobol.total(), 4);
+	assert!(cobol.cases.iter().all(|c| c.status == TestStatus::Error));
+	assert!(
+		cobol.cases[0]
+			.failure
+			.as_ref()
+			.unwrap()
+			.message
+			.contains("No executor registered for language 'cobol'")
+	);
+}
+
 #[tokio::test]
 async fn test_missing_file() {
 	let _dir = setup_test_dir();
@@ -249,7 +288,7 @@ expect = 10
 	)
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -260,7 +299,7 @@ expect = 10
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 
 	let alice = &results["alice"];
 	assert_eq!(alice.total_cases(), 1);
@@ -303,7 +342,7 @@ expect = "hello world"
 	)
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -314,7 +353,7 @@ expect = "hello world"
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 	assert_eq!(alice.test_results[0].cases[0].status, TestStatus::Passed);
 }
@@ -352,7 +391,7 @@ expect = 0.001
 	)
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -363,7 +402,7 @@ expect = 0.001
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 	assert_eq!(
 		alice.test_results[0].cases[0].status,
@@ -418,7 +457,7 @@ reference = "{}/solutions/lab5.py"
 	))
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -429,7 +468,7 @@ reference = "{}/solutions/lab5.py"
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 
 	assert_eq!(alice.total_cases(), 10, "Should have 10 generated cases");
@@ -475,7 +514,7 @@ rhai = "if a >= b { a } else { b }"
 	)
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -486,7 +525,7 @@ rhai = "if a >= b { a } else { b }"
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 
 	assert_eq!(alice.total_cases(), 5, "Should have 5 generated cases");
@@ -543,7 +582,7 @@ expect = 60
 	))
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -554,7 +593,7 @@ expect = 60
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 
 	assert_eq!(alice.total_cases(), 1);
@@ -633,7 +672,7 @@ expect = 20.0
 	))
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -644,7 +683,7 @@ expect = 20.0
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 
 	assert_eq!(alice.total_cases(), 2);
@@ -710,7 +749,7 @@ expect = [0, 1, 2]
 	))
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -721,7 +760,7 @@ expect = [0, 1, 2]
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 
 	assert_eq!(alice.total_cases(), 1);
@@ -777,7 +816,7 @@ args = []
 	))
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -788,7 +827,7 @@ args = []
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 
 	assert_eq!(
@@ -845,7 +884,7 @@ expect = 10
 	))
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -856,7 +895,7 @@ expect = 10
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 
 	assert_eq!(
@@ -918,7 +957,7 @@ expect = 5
 	))
 	.unwrap();
 
-	let executor = PythonExecutor::new();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
 	let submissions = SubmissionSet {
 		by_student: HashMap::from([(
 			"alice".to_string(),
@@ -929,7 +968,7 @@ expect = 5
 		)]),
 	};
 
-	let results = orchestrator::run_all(&submissions, &[spec], &executor, 10, Some(1)).await;
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
 	let alice = &results["alice"];
 
 	// With copy_refs=true (default), second case should still see original DATA
@@ -944,3 +983,1258 @@ expect = 5
 		"length should still be 5 because DATA was deepcopied per case"
 	);
 }
+
+#[tokio::test]
+async fn test_notebook_submission_maps_errors_to_cells() {
+	let dir = tempfile::tempdir().unwrap();
+	std::fs::write(
+		dir.path().join("dana_lab5.ipynb"),
+		r##"{
+  "cells": [
+    {"cell_type": "markdown", "source": ["# Lab 5"]},
+    {"cell_type": "code", "source": ["%load_ext autoreload\n", "!pip install nothing\n", "import math"]},
+    {"cell_type": "code", "source": ["def find_larger_number(a, b):\n", "    if a == b:\n", "        raise ValueError('tie')\n", "    return max(a, b)"]}
+  ]
+}"##,
+	)
+	.unwrap();
+
+	let submissions = scriptmark::discovery::discover_submissions(&[dir.path()], None).unwrap();
+	let files = &submissions.by_student["dana"];
+	let spec = test_spec();
+	let executor = PythonExecutor::new();
+
+	let passed = executor
+		.execute_case(files, &spec, &spec.cases[0], 10)
+		.await;
+	assert_eq!(passed.status, TestStatus::Passed);
+
+	let failed = executor
+		.execute_case(files, &spec, &spec.cases[1], 10)
+		.await;
+	assert_eq!(failed.status, TestStatus::Failed);
+	let details = failed.failure.unwrap().details;
+	assert!(
+		details.contains("dana_lab5.ipynb\", cell 3, line 3"),
+		"{details}"
+	);
+}
+
+#[tokio::test]
+async fn test_notebook_requirement_failures_point_at_cells() {
+	let dir = tempfile::tempdir().unwrap();
+	std::fs::write(
+		dir.path().join("dana_lab5.ipynb"),
+		r##"{
+  "cells": [
+    {"cell_type": "markdown", "source": ["# Lab 5"]},
+    {"cell_type": "code", "source": ["import math"]},
+    {"cell_type": "code", "source": ["def find_larger_number(a, b):\n", "    return max(a, b)"]}
+  ]
+}"##,
+	)
+	.unwrap();
+
+	let submissions = scriptmark::discovery::discover_submissions(&[dir.path()], None).unwrap();
+	let files = &submissions.by_student["dana"];
+	let mut spec = test_spec();
+	spec.requirements = Some(toml::from_str(r#"forbidden_calls = ["max"]"#).unwrap());
+
+	let results =
+		scriptmark::runner::requirements::check_requirements(files, &spec, "python3", 10).await;
+	assert_eq!(results.len(), 1);
+	assert_eq!(results[0].status, TestStatus::Failed);
+	assert_eq!(
+		results[0].failure.as_ref().unwrap().message,
+		"max() called on cell 3, line 2"
+	);
+}
+
+#[tokio::test]
+async fn test_io_cases_use_checkers() {
+	let dir = tempfile::tempdir().unwrap();
+	std::fs::write(
+		dir.path().join("erin_greet.py"),
+		"import sys\nname = input()\nprint(f'Hello, {name}!   ')\nprint()\nprint('warned', file=sys.stderr)\nsys.exit(3)\n",
+	)
+	.unwrap();
+	let files = vec![StudentFile {
+		path: dir.path().join("erin_greet.py"),
+		language: "python".to_string(),
+	}];
+	let spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "greet"
+file = "greet.py"
+language = "python"
+
+[[cases]]
+name = "exact"
+stdin = "Ada\n"
+expected_stdout = "Hello, Ada!"
+
+[[cases]]
+name = "text"
+stdin = "Ada\n"
+expected_stdout = "Hello, Ada!\n"
+check = "text"
+
+[[cases]]
+name = "regex"
+stdin = "Ada\n"
+check = { regex = "hello, \\w+!", flags = "i" }
+
+[[cases]]
+name = "context"
+stdin = "Ada\n"
+check = { rhai = "context.exit_code == 3 && context.stderr.contains(\"warned\")" }
+"#,
+	)
+	.unwrap();
+	let executor = PythonExecutor::new();
+
+	let exact = executor
+		.execute_case(&files, &spec, &spec.cases[0], 10)
+		.await;
+	assert_eq!(exact.status, TestStatus::Failed);
+	assert_eq!(exact.failure.unwrap().message, "stdout mismatch");
+
+	for case in &spec.cases[1..] {
+		let result = executor.execute_case(&files, &spec, case, 10).await;
+		assert_eq!(
+			result.status,
+			TestStatus::Passed,
+			"{}: {result:?}",
+			case.name
+		);
+	}
+}
+
+#[tokio::test]
+async fn test_io_exit_code_and_stderr() {
+	let dir = tempfile::tempdir().unwrap();
+	std::fs::write(
+		dir.path().join("erin_cli.py"),
+		"import sys\nif len(sys.stdin.read().split()) != 2:\n    print('usage: cli A B', file=sys.stderr)\n    sys.exit(2)\nprint('ok')\n",
+	)
+	.unwrap();
+	let files = vec![StudentFile {
+		path: dir.path().join("erin_cli.py"),
+		language: "python".to_string(),
+	}];
+	let spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "cli"
+file = "cli.py"
+language = "python"
+
+[[cases]]
+name = "usage error"
+stdin = "1\n"
+expect_exit_code = 2
+expected_stderr = "usage: cli A B\n"
+
+[[cases]]
+name = "usage pattern"
+stdin = ""
+expect_exit_code = 2
+stderr_check = { regex = "^usage:" }
+
+[[cases]]
+name = "wrong exit code"
+stdin = "1 2\n"
+expected_stdout = "ok\n"
+expect_exit_code = 1
+
+[[cases]]
+name = "stderr mismatch"
+stdin = "1\n"
+expected_stderr = "Usage: cli A B\n"
+"#,
+	)
+	.unwrap();
+	let executor = PythonExecutor::new();
+	let run = |i: usize| executor.execute_case(&files, &spec, &spec.cases[i], 10);
+
+	assert_eq!(run(0).await.status, TestStatus::Passed);
+	assert_eq!(run(1).await.status, TestStatus::Passed);
+
+	let wrong_code = run(2).await;
+	assert_eq!(wrong_code.status, TestStatus::Failed);
+	assert_eq!(
+		wrong_code.failure.unwrap().message,
+		"expected exit code 1, got 0"
+	);
+
+	let wrong_stderr = run(3).await;
+	assert_eq!(wrong_stderr.status, TestStatus::Failed);
+	assert_eq!(wrong_stderr.failure.unwrap().message, "stderr mismatch");
+}
+
+#[tokio::test]
+async fn test_io_argv_env_and_files() {
+	let dir = tempfile::tempdir().unwrap();
+	std::fs::create_dir(dir.path().join("data")).unwrap();
+	std::fs::write(dir.path().join("data/scores.csv"), "3\n4\n5\n").unwrap();
+	std::fs::write(
+		dir.path().join("erin_summary.py"),
+		"import os, sys\nlabel = open('label.txt').read().strip()\ntotal = sum(int(x) for x in open(sys.argv[1]))\nprint(label, total, os.environ['MODE'], sorted(os.listdir('.')))\n",
+	)
+	.unwrap();
+	std::fs::write(
+		dir.path().join("test_summary.toml"),
+		r#"
+[meta]
+name = "summary"
+file = "summary.py"
+language = "python"
+
+[[cases]]
+name = "sums the file named on argv"
+argv = ["scores.csv"]
+env = { MODE = "strict" }
+files = { "scores.csv" = { source = "data/scores.csv" }, "label.txt" = "total:" }
+expected_stdout = "total: 12 strict ['label.txt', 'scores.csv']\n"
+"#,
+	)
+	.unwrap();
+
+	let spec = scriptmark::spec_loader::load_spec(&dir.path().join("test_summary.toml")).unwrap();
+	let files = vec![StudentFile {
+		path: dir.path().join("erin_summary.py"),
+		language: "python".to_string(),
+	}];
+	let result = PythonExecutor::new()
+		.execute_case(&files, &spec, &spec.cases[0], 10)
+		.await;
+	assert_eq!(result.status, TestStatus::Passed, "{result:?}");
+}
+
+#[tokio::test]
+async fn test_chain_cases_get_their_own_env_and_files_and_check_them() {
+	let dir = tempfile::tempdir().unwrap();
+	let student = write_file(
+		dir.path(),
+		"erin_label.py",
+		"import os\n\ndef read_label(name):\n    return open(name).read() + os.environ.get('MODE', '-')\n",
+	);
+	// A per-case `function` runs the spec in chain mode
+	let spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "label"
+file = "label.py"
+language = "python"
+allowed_imports = ["os"]
+
+[[cases]]
+name = "file and env"
+function = "read_label"
+args = ["label.txt"]
+env = { MODE = "strict" }
+files = { "label.txt" = "total:" }
+expect = "total:strict"
+expect_files = { "label.txt" = "total:" }
+
+[[cases]]
+name = "fresh dir and env"
+function = "read_label"
+args = ["label.txt"]
+files = { "label.txt" = "sum:" }
+expect = "sum:-"
+
+[[cases]]
+name = "escaping file"
+function = "read_label"
+args = ["label.txt"]
+files = { "../label.txt" = "nope" }
+expect = "nope-"
+
+[[cases]]
+name = "missing report"
+function = "read_label"
+args = ["label.txt"]
+files = { "label.txt" = "a:" }
+expect = "a:-"
+expect_files = { "report.txt" = "a:" }
+
+[[cases]]
+name = "escaping expected file"
+function = "read_label"
+args = ["label.txt"]
+files = { "label.txt" = "a:" }
+expect = "a:-"
+expect_files = { "../label.txt" = "a:" }
+"#,
+	)
+	.unwrap();
+	let files = vec![StudentFile {
+		path: student.into(),
+		language: "python".to_string(),
+	}];
+
+	let results = PythonExecutor::new()
+		.execute_chain(&files, &spec, &spec.cases, 10)
+		.await;
+	assert_eq!(results.len(), 5);
+	assert_eq!(results[0].status, TestStatus::Passed, "{:?}", results[0]);
+	assert_eq!(results[1].status, TestStatus::Passed, "{:?}", results[1]);
+	assert_eq!(results[2].status, TestStatus::Error);
+	assert!(
+		results[2]
+			.failure
+			.as_ref()
+			.unwrap()
+			.message
+			.contains("must be a relative path")
+	);
+	assert_eq!(results[3].status, TestStatus::Failed);
+	assert_eq!(
+		results[3].failure.as_ref().unwrap().message,
+		"expected file 'report.txt' was not created"
+	);
+	assert_eq!(results[4].status, TestStatus::Error);
+	assert_eq!(
+		results[4].failure.as_ref().unwrap().message,
+		"Expected file '../label.txt' must be a relative path inside the working dir"
+	);
+}
+
+#[tokio::test]
+async fn test_expect_files_written_by_student() {
+	let dir = tempfile::tempdir().unwrap();
+	std::fs::write(
+		dir.path().join("erin_report.py"),
+		"def write_report(n):\n    with open('report.txt', 'w') as f:\n        f.write(f'total: {n}\\n')\n    return n\n\nif __name__ == '__main__':\n    write_report(int(input()))\n",
+	)
+	.unwrap();
+	let files = vec![StudentFile {
+		path: dir.path().join("erin_report.py"),
+		language: "python".to_string(),
+	}];
+	let io_spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "report"
+file = "report.py"
+language = "python"
+
+[[cases]]
+name = "writes report"
+stdin = "12\n"
+expect_files = { "report.txt" = "total: 12\n" }
+
+[[cases]]
+name = "wrong content"
+stdin = "5\n"
+expect_files = { "report.txt" = { expect = "total: 6", check = "text" } }
+
+[[cases]]
+name = "missing file"
+stdin = "5\n"
+expect_files = { "summary.txt" = "total: 5\n" }
+"#,
+	)
+	.unwrap();
+	let executor = PythonExecutor::new();
+	let run = |spec: &TestSpec, i: usize| {
+		let case = spec.cases[i].clone();
+		let spec = spec.clone();
+		let files = files.clone();
+		let executor = &executor;
+		async move { executor.execute_case(&files, &spec, &case, 10).await }
+	};
+
+	assert_eq!(run(&io_spec, 0).await.status, TestStatus::Passed);
+
+	let wrong = run(&io_spec, 1).await;
+	assert_eq!(wrong.status, TestStatus::Failed);
+	assert!(
+		wrong
+			.failure
+			.unwrap()
+			.message
+			.starts_with("file 'report.txt': text mismatch")
+	);
+
+	let missing = run(&io_spec, 2).await;
+	assert_eq!(missing.status, TestStatus::Failed);
+	assert_eq!(
+		missing.failure.unwrap().message,
+		"expected file 'summary.txt' was not created"
+	);
+
+	let fn_spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "report fn"
+file = "report.py"
+function = "write_report"
+language = "python"
+
+[[cases]]
+name = "function writes report"
+args = [3]
+expect = 3
+expect_files = { "report.txt" = { check = { regex = "total: 3" } } }
+"#,
+	)
+	.unwrap();
+	assert_eq!(run(&fn_spec, 0).await.status, TestStatus::Passed);
+}
+
+#[tokio::test]
+async fn test_class_case_reports_each_method_call() {
+	let dir = tempfile::tempdir().unwrap();
+	write_file(
+		dir.path(),
+		"alice_ds.py",
+		r#"
+class Stack:
+    def __init__(self, items=None):
+        self.items = list(items or [])
+
+    def push(self, x):
+        self.items.append(x)
+
+    def pop(self):
+        return self.items.pop()
+
+    def size(self):
+        return len(self.items) + 1  # off by one
+"#,
+	);
+
+	let spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "stack"
+file = "ds.py"
+language = "python"
+
+[[cases]]
+name = "stack ops"
+class = "Stack"
+init_args = [[1]]
+calls = [
+  { method = "push", args = [2] },
+  { method = "pop", expect = 2 },
+  { method = "size", expect = 1 },
+  { name = "pop last", method = "pop", expect = 1 },
+  { method = "pop", expect_error = "IndexError" },
+]
+
+[[cases]]
+name = "bad constructor"
+class = "Stack"
+init_args = [1, 2, 3]
+calls = [{ method = "size", expect = 0 }]
+"#,
+	)
+	.unwrap();
+
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
+	let submissions = SubmissionSet {
+		by_student: HashMap::from([(
+			"alice".to_string(),
+			vec![StudentFile {
+				path: dir.path().join("alice_ds.py"),
+				language: "python".to_string(),
+			}],
+		)]),
+	};
+
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
+	let cases = &results["alice"].test_results[0].cases;
+	let summary: Vec<(&str, TestStatus)> = cases
+		.iter()
+		.map(|c| (c.case_name.as_str(), c.status))
+		.collect();
+	assert_eq!(
+		summary,
+		vec![
+			("stack ops > 1. push", TestStatus::Passed),
+			("stack ops > 2. pop", TestStatus::Passed),
+			("stack ops > 3. size", TestStatus::Failed),
+			("stack ops > pop last", TestStatus::Passed),
+			("stack ops > 5. pop", TestStatus::Passed),
+			("bad constructor > 1. size", TestStatus::Failed),
+		]
+	);
+	let message = &cases[5].failure.as_ref().unwrap().message;
+	assert!(
+		message.starts_with("Constructing Stack failed: TypeError"),
+		"{message}"
+	);
+}
+
+#[tokio::test]
+async fn test_kwargs_and_tagged_python_values() {
+	let dir = tempfile::tempdir().unwrap();
+	write_file(
+		dir.path(),
+		"alice_kw.py",
+		r#"
+import math
+
+def describe(point, *, tags, scale=1, missing=0, blob=b""):
+    assert isinstance(point, tuple) and isinstance(tags, set)
+    assert missing is None and isinstance(blob, bytes)
+    return [point[0] * scale, sorted(tags), len(blob)]
+
+def is_inf(x):
+    return math.isinf(x)
+"#,
+	);
+	let files = vec![StudentFile {
+		path: dir.path().join("alice_kw.py"),
+		language: "python".to_string(),
+	}];
+	let spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "kwargs"
+file = "kw.py"
+function = "describe"
+language = "python"
+
+[[cases]]
+name = "keyword-only and tags"
+args = [{ "$tuple" = [2, 3] }]
+kwargs = { tags = { "$set" = ["b", "a", "a"] }, scale = 5, missing = { "$none" = true }, blob = { "$bytes" = "abc" } }
+expect = [10, ["a", "b"], 3]
+
+[[cases]]
+name = "infinity in chain mode"
+function = "is_inf"
+args = [{ "$float" = "-inf" }]
+expect = true
+"#,
+	)
+	.unwrap();
+	let executor = PythonExecutor::new();
+
+	let single = executor
+		.execute_case(&files, &spec, &spec.cases[0], 10)
+		.await;
+	assert_eq!(single.status, TestStatus::Passed, "{single:?}");
+
+	let chained = executor.execute_chain(&files, &spec, &spec.cases, 10).await;
+	assert!(
+		chained.iter().all(|r| r.status == TestStatus::Passed),
+		"{chained:?}"
+	);
+}
+
+#[tokio::test]
+async fn test_expect_type_and_tagged_results() {
+	let dir = tempfile::tempdir().unwrap();
+	write_file(
+		dir.path(),
+		"alice_types.py",
+		r#"
+def pair(a, b):
+    return (a, b)
+
+def as_list(a, b):
+    return [a, b]
+
+def letters(s):
+    return set(s)
+
+def huge():
+    return float("inf")
+"#,
+	);
+	let files = vec![StudentFile {
+		path: dir.path().join("alice_types.py"),
+		language: "python".to_string(),
+	}];
+	let spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "types"
+file = "types.py"
+function = "pair"
+language = "python"
+
+[[cases]]
+name = "tuple"
+args = [1, 2.0]
+expect = [1, 2]
+expect_type = "tuple"
+
+[[cases]]
+name = "list is not a tuple"
+function = "as_list"
+args = [1, 2]
+expect = [1, 2]
+expect_type = "tuple"
+
+[[cases]]
+name = "set"
+function = "letters"
+args = ["abca"]
+expect = ["a", "b", "c"]
+expect_type = "set"
+
+[[cases]]
+name = "infinity"
+function = "huge"
+expect = { "$float" = "inf" }
+"#,
+	)
+	.unwrap();
+	let executor = PythonExecutor::new();
+
+	let single = executor
+		.execute_case(&files, &spec, &spec.cases[0], 10)
+		.await;
+	assert_eq!(single.status, TestStatus::Passed, "{single:?}");
+
+	let chained = executor.execute_chain(&files, &spec, &spec.cases, 10).await;
+	let statuses: Vec<_> = chained.iter().map(|r| r.status).collect();
+	assert_eq!(
+		statuses,
+		[
+			TestStatus::Passed,
+			TestStatus::Failed,
+			TestStatus::Passed,
+			TestStatus::Passed
+		],
+		"{chained:?}"
+	);
+	assert_eq!(
+		chained[1].failure.as_ref().unwrap().message,
+		"expected a value of type tuple, got list [1,2]"
+	);
+}
+
+#[tokio::test]
+async fn test_function_stdout_is_captured_and_checked() {
+	let dir = tempfile::tempdir().unwrap();
+	write_file(
+		dir.path(),
+		"alice_tri.py",
+		r#"
+print("loading")
+
+def triangle(n):
+    for i in range(1, n + 1):
+        print("*" * i)
+    return n * (n + 1) // 2
+
+def square(n):
+    print("=" * n)
+"#,
+	);
+	let files = vec![StudentFile {
+		path: dir.path().join("alice_tri.py"),
+		language: "python".to_string(),
+	}];
+	let spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "triangle"
+file = "tri.py"
+function = "triangle"
+language = "python"
+
+[[cases]]
+name = "prints and returns"
+args = [3]
+expect = 6
+expected_stdout = "*\n**\n***\n"
+
+[[cases]]
+name = "checker sees stdout"
+args = [2]
+check = { rhai = "context.stdout == \"*\\n**\\n\" && result == 3" }
+
+[[cases]]
+name = "wrong output"
+function = "square"
+args = [2]
+expected_stdout = "==\n==\n"
+"#,
+	)
+	.unwrap();
+	let executor = PythonExecutor::new();
+
+	for case in &spec.cases[..2] {
+		let result = executor.execute_case(&files, &spec, case, 10).await;
+		assert_eq!(result.status, TestStatus::Passed, "{result:?}");
+	}
+
+	let chained = executor.execute_chain(&files, &spec, &spec.cases, 10).await;
+	assert_eq!(chained[0].status, TestStatus::Passed, "{chained:?}");
+	assert_eq!(chained[2].status, TestStatus::Failed, "{chained:?}");
+	let failure = chained[2].failure.as_ref().unwrap();
+	assert_eq!(failure.message, "stdout mismatch");
+	assert_eq!(chained[2].actual.as_deref(), Some("==\n"));
+}
+
+#[tokio::test]
+async fn test_scripted_inputs_feed_input() {
+	let dir = tempfile::tempdir().unwrap();
+	write_file(
+		dir.path(),
+		"alice_ask.py",
+		r#"
+name = input("top-level: ")
+
+def ask_sum():
+    a = int(input("a? "))
+    b = int(input("b? "))
+    return a + b
+
+class Greeter:
+    def __init__(self):
+        self.name = input()
+
+    def greet(self):
+        return f"{input()}, {self.name}"
+"#,
+	);
+	let files = vec![StudentFile {
+		path: dir.path().join("alice_ask.py"),
+		language: "python".to_string(),
+	}];
+	let spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "inputs"
+file = "ask.py"
+function = "ask_sum"
+language = "python"
+
+[[cases]]
+name = "two answers"
+inputs = ["3", "4"]
+expect = 7
+expected_stdout = "a? b? "
+
+[[cases]]
+name = "runs out"
+inputs = ["3"]
+expect = 7
+
+[[cases]]
+name = "greeter"
+class = "Greeter"
+inputs = ["Ada", "Hello"]
+calls = [{ method = "greet", expect = "Hello, Ada" }]
+"#,
+	)
+	.unwrap();
+	let executor = PythonExecutor::new();
+
+	let single = executor
+		.execute_case(&files, &spec, &spec.cases[0], 10)
+		.await;
+	assert_eq!(single.status, TestStatus::Passed, "{single:?}");
+	let exhausted = executor
+		.execute_case(&files, &spec, &spec.cases[1], 10)
+		.await;
+	assert_eq!(exhausted.status, TestStatus::Failed);
+	assert_eq!(
+		exhausted.failure.unwrap().message,
+		"EOFError: input() called more times than the case's 1 scripted inputs"
+	);
+
+	let chained = executor.execute_chain(&files, &spec, &spec.cases, 10).await;
+	let statuses: Vec<_> = chained.iter().map(|r| r.status).collect();
+	assert_eq!(
+		statuses,
+		[TestStatus::Passed, TestStatus::Failed, TestStatus::Passed],
+		"{chained:?}"
+	);
+}
+
+#[tokio::test]
+async fn test_requirements_are_scored_cases() {
+	let dir = setup_test_dir();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
+	let submissions = SubmissionSet {
+		by_student: HashMap::from([(
+			"alice".to_string(),
+			vec![StudentFile {
+				path: dir.path().join("alice_lab5.py"),
+				language: "python".to_string(),
+			}],
+		)]),
+	};
+	let mut spec = test_spec();
+	spec.requirements = Some(Requirements {
+		forbidden_calls: vec!["max".to_string(), "sorted".to_string()],
+		..Default::default()
+	});
+
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
+	let alice = &results["alice"];
+	assert_eq!(alice.total_cases(), 6);
+	assert_eq!(alice.total_passed(), 5);
+	let cases = &alice.test_results[0].cases;
+	assert_eq!(cases[4].case_name, "requirement: no max()");
+	assert_eq!(
+		cases[4].failure.as_ref().unwrap().message,
+		"max() called on line 5"
+	);
+	assert_eq!(cases[5].status, TestStatus::Passed);
+}
+
+#[tokio::test]
+async fn test_fuzzy_matches_are_reported_and_strict_names_fails_them() {
+	let dir = tempfile::tempdir().unwrap();
+	write_file(
+		dir.path(),
+		"alice_minmax.py",
+		"def find_min(xs):\n    return min(xs)\n\ndef total(xs):\n    return sum(xs)\n",
+	);
+	let files = vec![StudentFile {
+		path: dir.path().join("alice_minmax.py"),
+		language: "python".to_string(),
+	}];
+	let mut spec: TestSpec = toml::from_str(
+		r#"
+[meta]
+name = "minmax"
+file = "minmax.py"
+function = "find_max"
+language = "python"
+
+[[cases]]
+name = "single element"
+args = [[4]]
+expect = 4
+
+[[cases]]
+name = "sum"
+function = "total"
+args = [[1, 2]]
+expect = 3
+"#,
+	)
+	.unwrap();
+	let executor = PythonExecutor::new();
+
+	let single = executor
+		.execute_case(&files, &spec, &spec.cases[0], 10)
+		.await;
+	assert_eq!(single.status, TestStatus::Passed, "{single:?}");
+	assert_eq!(single.matched_name.as_deref(), Some("find_min"));
+
+	let chained = executor.execute_chain(&files, &spec, &spec.cases, 10).await;
+	assert_eq!(chained[0].matched_name.as_deref(), Some("find_min"));
+	assert_eq!(chained[1].matched_name, None);
+
+	spec.meta.strict_names = true;
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
+	let submissions = SubmissionSet {
+		by_student: HashMap::from([("alice".to_string(), files)]),
+	};
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, Some(1)).await;
+	let cases = &results["alice"].test_results[0].cases;
+	assert_eq!(cases[0].status, TestStatus::Failed);
+	assert_eq!(
+		cases[0].failure.as_ref().unwrap().message,
+		"called 'find_min' by fuzzy name match, which strict_names forbids"
+	);
+	assert_eq!(cases[1].status, TestStatus::Passed);
+}
+
+#[tokio::test]
+async fn test_parametrize_with_python_oracle() {
+	let dir = tempfile::tempdir().unwrap();
+	write_file(
+		dir.path(),
+		"alice_lab5.py",
+		"def find_max(a, b):\n    return max(a, b)\n",
+	);
+	let oracle = write_file(
+		dir.path(),
+		"oracle.py",
+		"import json, sys\ndata = json.load(sys.stdin)\nprint(json.dumps(max(data['named']['a'], data['args'][1])))\n",
+	);
+	let broken = write_file(dir.path(), "broken.py", "raise RuntimeError('no answer')\n");
+	let spec_for = |script: &str| -> TestSpec {
+		toml::from_str(&format!(
+			r#"
+[meta]
+name = "python_oracle"
+file = "lab5.py"
+function = "find_max"
+language = "python"
+
+[[cases]]
+name = "random max"
+
+[cases.parametrize]
+count = 5
+seed = 7
+
+[cases.parametrize.args]
+a = "int(-50, 50)"
+b = "int(-50, 50)"
+
+[cases.parametrize.oracle]
+python = "{script}"
+"#
+		))
+		.unwrap()
+	};
+
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
+	let submissions = SubmissionSet {
+		by_student: HashMap::from([(
+			"alice".to_string(),
+			vec![StudentFile {
+				path: dir.path().join("alice_lab5.py"),
+				language: "python".to_string(),
+			}],
+		)]),
+	};
+
+	let specs = [spec_for(&oracle), spec_for(&broken)];
+	let results = orchestrator::run_all(&submissions, &specs, &executors, 10, Some(1)).await;
+	let alice = &results["alice"];
+	let good = &alice.test_results[0];
+	assert_eq!(good.total(), 5);
+	assert_eq!(good.passed(), 5, "{:?}", good.cases);
+
+	let bad = &alice.test_results[1];
+	assert_eq!(bad.total(), 5);
+	for case in &bad.cases {
+		assert_eq!(case.status, TestStatus::Error);
+		let failure = case.failure.as_ref().unwrap();
+		assert!(
+			failure.message.starts_with("Oracle failed: Python oracle"),
+			"{failure:?}"
+		);
+		assert!(failure.details.contains("no answer"), "{failure:?}");
+	}
+
+	// The oracle runs under the executor's interpreter, not a bare `python3`
+	let missing = ExecutorRegistry::new()
+		.with_executor(PythonExecutor::with_python_cmd("/nonexistent/python3"));
+	let results = orchestrator::run_all(&submissions, &specs[..1], &missing, 10, Some(1)).await;
+	for case in &results["alice"].test_results[0].cases {
+		let failure = case.failure.as_ref().unwrap();
+		assert!(
+			failure
+				.message
+				.starts_with("Oracle failed: Failed to spawn"),
+			"{failure:?}"
+		);
+	}
+}
+
+#[tokio::test]
+async fn test_oracle_failures_are_errors_and_rhai_arrays_convert() {
+	let dir = tempfile::tempdir().unwrap();
+	write_file(
+		dir.path(),
+		"alice_lab6.py",
+		"def sort_nums(nums):\n    return sorted(nums)\n",
+	);
+	let spec_for = |oracle: &str| -> TestSpec {
+		toml::from_str(&format!(
+			r#"
+[meta]
+name = "oracles"
+file = "lab6.py"
+function = "sort_nums"
+language = "python"
+
+[[cases]]
+name = "random sort"
+
+[cases.parametrize]
+count = 4
+seed = 3
+
+[cases.parametrize.args]
+nums = "list(int(-20, 20), 1, 8)"
+
+[cases.parametrize.oracle]
+{oracle}
+"#
+		))
+		.unwrap()
+	};
+
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
+	let submissions = SubmissionSet {
+		by_student: HashMap::from([(
+			"alice".to_string(),
+			vec![StudentFile {
+				path: dir.path().join("alice_lab6.py"),
+				language: "python".to_string(),
+			}],
+		)]),
+	};
+	let missing = dir.path().join("no_such_solution.py");
+	let specs = [
+		spec_for(r#"rhai = "nums.sort(); nums""#),
+		spec_for(r#"rhai = "nums.sort(""#),
+		spec_for(&format!("reference = \"{}\"", missing.display())),
+	];
+	let results = orchestrator::run_all(&submissions, &specs, &executors, 10, Some(1)).await;
+	let alice = &results["alice"];
+
+	let sorted = &alice.test_results[0];
+	assert_eq!(sorted.passed(), 4, "{:?}", sorted.cases);
+	for case in &sorted.cases {
+		assert!(
+			case.expected.as_deref().unwrap().starts_with('['),
+			"{case:?}"
+		);
+	}
+
+	for (result, prefix) in [
+		(&alice.test_results[1], "Oracle failed: Rhai oracle"),
+		(&alice.test_results[2], "Oracle failed: Reference oracle"),
+	] {
+		assert_eq!(result.total(), 4);
+		for case in &result.cases {
+			assert_eq!(case.status, TestStatus::Error, "{case:?}");
+			let failure = case.failure.as_ref().unwrap();
+			assert!(failure.message.starts_with(prefix), "{failure:?}");
+		}
+	}
+}
+
+#[tokio::test]
+async fn test_reference_expectations_are_cached_per_spec() {
+	let dir = tempfile::tempdir().unwrap();
+	let reference = write_file(
+		dir.path(),
+		"solution.py",
+		"def square(n):\n    return n * n\n",
+	);
+	let mut by_student = HashMap::new();
+	for sid in ["alice", "bob", "carol"] {
+		let path = write_file(
+			dir.path(),
+			&format!("{sid}_lab7.py"),
+			"def square(n):\n    return n ** 2\n",
+		);
+		by_student.insert(
+			sid.to_string(),
+			vec![StudentFile {
+				path: path.into(),
+				language: "python".to_string(),
+			}],
+		);
+	}
+	let submissions = SubmissionSet { by_student };
+	let spec: TestSpec = toml::from_str(&format!(
+		r#"
+[meta]
+name = "cached"
+file = "lab7.py"
+function = "square"
+language = "python"
+
+[[cases]]
+name = "random square"
+
+[cases.parametrize]
+count = 6
+seed = 11
+
+[cases.parametrize.args]
+n = "int(-30, 30)"
+
+[cases.parametrize.oracle]
+reference = "{reference}"
+"#
+	))
+	.unwrap();
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
+	let specs = [spec];
+
+	let results = orchestrator::run_all(&submissions, &specs, &executors, 10, None).await;
+	for report in results.values() {
+		assert_eq!(report.test_results[0].passed(), 6, "{report:?}");
+	}
+
+	let cache_path = dir.path().join("solution.expectations.json");
+	let mut cache: serde_json::Value =
+		serde_json::from_str(&std::fs::read_to_string(&cache_path).unwrap()).unwrap();
+	// A fixed hash, so the file stays valid across toolchains and releases
+	assert_eq!(cache["source_hash"], "fnv1a64:9d48080fe4e8386e");
+	let entries = cache["expectations"].as_array_mut().unwrap();
+	assert_eq!(entries.len(), 6);
+	for entry in entries.iter() {
+		let n = entry["args"][0].as_i64().unwrap();
+		assert_eq!(entry["expect"], serde_json::json!(n * n));
+		assert_eq!(entry["seed"], 11);
+	}
+
+	// A second run reuses the file instead of running the reference again
+	entries[0]["expect"] = serde_json::json!("cached");
+	std::fs::write(&cache_path, cache.to_string()).unwrap();
+	let results = orchestrator::run_all(&submissions, &specs, &executors, 10, None).await;
+	for report in results.values() {
+		assert_eq!(report.test_results[0].passed(), 5, "{report:?}");
+	}
+
+	// Editing the reference invalidates the cache
+	std::fs::write(&reference, "def square(n):\n    return n * n  # edited\n").unwrap();
+	let results = orchestrator::run_all(&submissions, &specs, &executors, 10, None).await;
+	for report in results.values() {
+		assert_eq!(report.test_results[0].passed(), 6, "{report:?}");
+	}
+}
+
+#[tokio::test]
+async fn test_reference_raise_is_reported_in_case_order() {
+	let dir = tempfile::tempdir().unwrap();
+	let reference = write_file(
+		dir.path(),
+		"solution.py",
+		"def root(n):\n    if n < 0:\n        raise ValueError('negative')\n    return n\n",
+	);
+	let student = write_file(dir.path(), "alice_lab8.py", "def root(n):\n    return n\n");
+	let spec: TestSpec = toml::from_str(&format!(
+		r#"
+[meta]
+name = "raising"
+file = "lab8.py"
+function = "root"
+language = "python"
+
+[[cases]]
+name = "first"
+args = [1]
+expect = 1
+
+[[cases]]
+name = "random root"
+
+[cases.parametrize]
+count = 8
+seed = 5
+
+[cases.parametrize.args]
+n = "int(-30, 30)"
+
+[cases.parametrize.oracle]
+reference = "{reference}"
+
+[[cases]]
+name = "last"
+args = [2]
+expect = 2
+"#
+	))
+	.unwrap();
+	let submissions = SubmissionSet {
+		by_student: HashMap::from([(
+			"alice".to_string(),
+			vec![StudentFile {
+				path: student.into(),
+				language: "python".to_string(),
+			}],
+		)]),
+	};
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
+	let results = orchestrator::run_all(&submissions, &[spec], &executors, 10, None).await;
+	let cases = &results["alice"].test_results[0].cases;
+
+	let names: Vec<&str> = cases.iter().map(|c| c.case_name.as_str()).collect();
+	let mut expected = vec!["first".to_string()];
+	expected.extend((0..8).map(|i| format!("random root [{i}]")));
+	expected.push("last".to_string());
+	assert_eq!(names, expected);
+
+	let errors: Vec<_> = cases
+		.iter()
+		.filter(|c| c.status == TestStatus::Error)
+		.collect();
+	assert!(!errors.is_empty() && errors.len() < 8, "{cases:?}");
+	for case in errors {
+		let failure = case.failure.as_ref().unwrap();
+		assert!(
+			failure.message.contains("raised: ValueError: negative"),
+			"{failure:?}"
+		);
+	}
+}
+
+#[tokio::test]
+async fn test_dependent_generators_with_explicit_order() {
+	let dir = tempfile::tempdir().unwrap();
+	write_file(
+		dir.path(),
+		"alice_lab8.py",
+		"def pick(nums, k, target):\n    return nums[k] if target in nums else None\n",
+	);
+	let spec_for = |args: &str| -> TestSpec {
+		toml::from_str(&format!(
+			r#"
+[meta]
+name = "dependent"
+file = "lab8.py"
+function = "pick"
+language = "python"
+
+[[cases]]
+name = "random index"
+
+[cases.parametrize]
+count = 8
+seed = 21
+order = ["nums", "k", "target"]
+
+[cases.parametrize.args]
+{args}
+
+[cases.parametrize.oracle]
+rhai = "nums[k]"
+"#
+		))
+		.unwrap()
+	};
+	let specs = [
+		spec_for(
+			"nums = \"list(int(-9, 9), 1, 6)\"\nk = \"int(0, len(nums) - 1)\"\ntarget = \"choice($nums)\"",
+		),
+		spec_for(
+			"nums = \"list(int(-9, 9), 1, 6)\"\nk = \"int(0, len($target))\"\ntarget = \"choice($nums)\"",
+		),
+	];
+
+	let executors = ExecutorRegistry::new().with_executor(PythonExecutor::new());
+	let submissions = SubmissionSet {
+		by_student: HashMap::from([(
+			"alice".to_string(),
+			vec![StudentFile {
+				path: dir.path().join("alice_lab8.py"),
+				language: "python".to_string(),
+			}],
+		)]),
+	};
+	let results = orchestrator::run_all(&submissions, &specs, &executors, 10, Some(1)).await;
+	let alice = &results["alice"];
+
+	let good = &alice.test_results[0];
+	assert_eq!(good.total(), 8);
+	assert_eq!(good.passed(), 8, "{:?}", good.cases);
+
+	let bad = &alice.test_results[1];
+	assert_eq!(bad.total(), 8);
+	for case in &bad.cases {
+		assert_eq!(case.status, TestStatus::Error);
+		let failure = case.failure.as_ref().unwrap();
+		assert!(
+			failure
+				.message
+				.starts_with("Generator failed: argument 'k'"),
+			"{failure:?}"
+		);
+	}
+}